@@ -0,0 +1,207 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::{
+    config::{Config, FrontMatterFormat, Sub},
+    utils::{
+        alternate_path,
+        casing::{convert_case, normalized_name},
+        clock::Clock,
+        command_folder_path,
+        frontmatter::{merge_front_matter, remove_front_matter_key, FrontMatterValue},
+        links::LinkIndex,
+        markdown_files, resolve_note_path,
+        trash::{move_to_trash, restore_from_trash, trash_folder, trashed_notes, TrashedNote},
+        vault::{archive_folder, vault_folders},
+        write_atomically,
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct MovedNote {
+    pub from: String,
+    pub to: String,
+    /// The notes that still link to the moved note.
+    pub linked_from: Vec<String>,
+}
+
+/// Moves a note into the archive folder, keeping its path relative to the note folder and
+/// recording the date in an `archived` front matter key.
+pub fn handle_archive_command(note: &str) -> Result<MovedNote, Box<dyn std::error::Error>> {
+    let note_folder = PathBuf::from(command_folder_path(Sub::Note)?);
+    let archive = archive_folder()?;
+    let path = PathBuf::from(resolve_note_path(note)?);
+
+    if path.starts_with(&archive) {
+        return Err(io::Error::other(format!("{} is already archived", path.display())).into());
+    }
+    let relative = path
+        .strip_prefix(&note_folder)
+        .map_err(|_| io::Error::other(format!("{} is not in the note folder", path.display())))?;
+    let target = archive.join(relative);
+    if target.exists() {
+        return Err(io::Error::other(format!("{} already exists", target.display())).into());
+    }
+
+    let linked_from = linked_from(&path)?;
+    let format = Config::read()?
+        .general
+        .front_matter
+        .unwrap_or(FrontMatterFormat::Yaml);
+    let contents = remove_front_matter_key(&fs::read_to_string(&path)?, "archived");
+    let archived = [(
+        String::from("archived"),
+        FrontMatterValue::Date(Clock::load()?.today()),
+    )];
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomically(
+        &target.to_string_lossy(),
+        &merge_front_matter(&contents, &archived, format),
+    )?;
+    fs::remove_file(&path).map_err(|err| {
+        let _ = fs::remove_file(&target);
+        err
+    })?;
+
+    Ok(MovedNote {
+        from: path.to_string_lossy().into_owned(),
+        to: target.to_string_lossy().into_owned(),
+        linked_from,
+    })
+}
+
+/// Moves a note of the note, journal or archive folder to the trash.
+pub fn handle_rm_command(note: &str) -> Result<MovedNote, Box<dyn std::error::Error>> {
+    let path = PathBuf::from(resolve_note_path(note)?);
+    let mut folders = vault_folders()?;
+    folders.push(archive_folder()?);
+    if !folders.iter().any(|folder| path.starts_with(folder)) {
+        return Err(io::Error::other(format!(
+            "{} is not in the note, journal or archive folder",
+            path.display()
+        ))
+        .into());
+    }
+
+    let linked_from = linked_from(&path)?;
+    let file = move_to_trash(&trash_folder()?, &path, Clock::load()?.now.naive_local())?;
+
+    Ok(MovedNote {
+        from: path.to_string_lossy().into_owned(),
+        to: file.to_string_lossy().into_owned(),
+        linked_from,
+    })
+}
+
+/// Moves the most recently deleted note matching `note` out of the trash, or else the matching
+/// note out of the archive, back to where it was.
+pub fn handle_restore_command(note: &str) -> Result<MovedNote, Box<dyn std::error::Error>> {
+    let trashed = handle_restore_list_command()?;
+    if let Some(trashed) = find_by_name(&trashed, note, |trashed| Path::new(&trashed.path)) {
+        restore_from_trash(trashed)?;
+
+        return Ok(MovedNote {
+            from: trashed.file.to_string_lossy().into_owned(),
+            to: trashed.path.clone(),
+            linked_from: Vec::new(),
+        });
+    }
+
+    let archive = archive_folder()?;
+    let archived = markdown_files(&archive);
+    let Some(path) = find_by_name(&archived, note, PathBuf::as_path) else {
+        return Err(
+            io::Error::other(format!("no deleted or archived note matches '{note}'")).into(),
+        );
+    };
+
+    let note_folder = PathBuf::from(command_folder_path(Sub::Note)?);
+    let target = note_folder.join(path.strip_prefix(&archive)?);
+    if target.exists() {
+        return Err(io::Error::other(format!(
+            "{} already exists, move it away to restore the archived note",
+            target.display()
+        ))
+        .into());
+    }
+
+    let contents = remove_front_matter_key(&fs::read_to_string(path)?, "archived");
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_atomically(&target.to_string_lossy(), &contents)?;
+    fs::remove_file(path)?;
+
+    Ok(MovedNote {
+        from: path.to_string_lossy().into_owned(),
+        to: target.to_string_lossy().into_owned(),
+        linked_from: Vec::new(),
+    })
+}
+
+/// The notes in the trash that were deleted from the note, journal or archive folder.
+pub fn handle_restore_list_command() -> Result<Vec<TrashedNote>, Box<dyn std::error::Error>> {
+    let mut folders = vault_folders()?;
+    folders.push(archive_folder()?);
+
+    Ok(trashed_notes(&trash_folder()?)
+        .into_iter()
+        .filter(|trashed| {
+            folders
+                .iter()
+                .any(|folder| Path::new(&trashed.path).starts_with(folder))
+        })
+        .collect())
+}
+
+fn linked_from(path: &Path) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut sources: Vec<String> = LinkIndex::build()?
+        .backlinks(&[&name])
+        .into_iter()
+        .map(|link| link.source.path.clone())
+        .collect();
+    sources.sort();
+    sources.dedup();
+
+    Ok(sources)
+}
+
+/// The item whose path is `query`, or whose file name matches it like `check_note_name`: as
+/// typed or converted to the configured case style first, then ignoring case style and accents.
+fn find_by_name<'a, T>(
+    items: &'a [T],
+    query: &str,
+    path_of: impl Fn(&T) -> &Path,
+) -> Option<&'a T> {
+    let query_path = PathBuf::from(alternate_path(query.to_owned()));
+    let name = query.trim_end_matches(".md");
+    let converted = convert_case(name.to_owned());
+    let stem = |item: &T| {
+        path_of(item)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+
+    items
+        .iter()
+        .find(|item| path_of(item) == query_path || stem(item) == name || stem(item) == converted)
+        .or_else(|| {
+            let normalized = normalized_name(name);
+            items
+                .iter()
+                .find(|item| normalized_name(&stem(item)) == normalized)
+        })
+}
@@ -0,0 +1,42 @@
+use crate::{
+    config::Config,
+    journal::handle_journal_command,
+    utils::{
+        capture::{append_entry, inbox_path, Append},
+        clock::Clock,
+        date::Calendar,
+        open_path_in_editor,
+        placeholder::TemplateContext,
+    },
+};
+
+/// Appends `append` to the configured inbox, which is either a note or today's journal entry.
+pub fn handle_capture_command(
+    append: Append,
+    edit: bool,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let capture = Config::read()?.capture.unwrap_or_default();
+    let append = append
+        .with_template(capture.template)
+        .with_default_section(capture.section);
+
+    let path = match capture.journal {
+        Some(journal) => handle_journal_command(&journal, None, None, Some(&append))?,
+        None => {
+            let path = inbox_path(capture.inbox.as_deref())?;
+            let clock = Clock::load()?;
+            let context = TemplateContext::new(String::from("Inbox"), clock.today())
+                .with_calendar(Calendar::load()?)
+                .with_now(clock.now);
+
+            append_entry(&path, &append, context)?;
+            path
+        }
+    };
+
+    if edit {
+        open_path_in_editor(&path)?;
+    }
+
+    Ok(path)
+}
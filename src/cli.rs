@@ -1,7 +1,7 @@
 use std::{env, ffi::OsStr, path::PathBuf};
 
 use clap::{
-    builder::PossibleValuesParser, Args, Command, CommandFactory, FromArgMatches, Parser,
+    builder::PossibleValuesParser, ArgGroup, Args, Command, CommandFactory, FromArgMatches, Parser,
     Subcommand,
 };
 use clap_complete::{
@@ -9,7 +9,11 @@ use clap_complete::{
     engine::{ArgValueCompleter, CompletionCandidate},
 };
 
-use crate::utils::template::{journal_entries, template_entries};
+use crate::utils::{
+    capture::Append,
+    markdown::InsertPosition,
+    template::{journal_entries, template_entries},
+};
 
 /// A Second Brain helper
 #[derive(Parser)]
@@ -23,6 +27,12 @@ pub struct Cli {
     /// Path to a custom config file
     #[arg(short = 'c', long, global = true, value_name = "PATH")]
     pub config: Option<PathBuf>,
+    /// Time zone used for the current time, e.g. UTC or Europe/Lisbon
+    #[arg(long, global = true, value_name = "TZ")]
+    pub tz: Option<String>,
+    /// Pretend the current time is this one, e.g. 2026-04-19T08:30
+    #[arg(long, global = true, value_name = "DATETIME")]
+    pub now: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -33,6 +43,34 @@ pub enum SubCommand {
     /// Create a journal note
     #[command(alias = "j")]
     Journal(JournalArgs),
+    /// Append a quick note to the inbox
+    Capture(CaptureArgs),
+    /// Insert a rendered template into an existing note
+    Insert(InsertArgs),
+    /// Show the links of a note, or the links that point nowhere
+    Links(LinksArgs),
+    /// Rename or move a note and rewrite the links pointing to it
+    Mv(MvArgs),
+    /// Bring existing notes in line with the configuration
+    Migrate(MigrateArgs),
+    /// Move a note into the archive folder
+    Archive(NoteTargetArgs),
+    /// Move a note to the trash
+    Rm(NoteTargetArgs),
+    /// Bring a note back from the trash or the archive
+    Restore(RestoreArgs),
+    /// Open an existing note or journal entry by fuzzy name
+    Open(OpenArgs),
+    /// Search the notes and journal entries
+    Search(SearchArgs),
+    /// List the tags in use, or the notes carrying a tag
+    Tags(TagsArgs),
+    /// Count notes, journal entries and words, and show journaling streaks
+    Stats(StatsArgs),
+    /// Manage the note index used by search and links
+    Index(IndexArgs),
+    /// Check the vault for problems
+    Doctor(DoctorArgs),
     /// Generate shell completion scripts
     Completion(CompletionArgs),
     /// List metadata for templates and journals
@@ -45,6 +83,14 @@ pub struct NoteArgs {
     pub template: String,
     #[arg(index = 2, required = true, num_args = 1..)]
     pub name: Vec<String>,
+    /// Tag rendered by {{tags}} in the template, can be repeated
+    #[arg(long = "tag", value_name = "TAG")]
+    pub tags: Vec<String>,
+    /// Other name of the note, written to the front matter, can be repeated
+    #[arg(long = "alias", value_name = "NAME")]
+    pub aliases: Vec<String>,
+    #[command(flatten)]
+    pub append: AppendArgs,
 }
 
 #[derive(Args)]
@@ -53,8 +99,228 @@ pub struct JournalArgs {
     pub name: String,
     #[arg(long)]
     pub date: Option<String>,
-    #[arg(long, num_args = 1..=2, allow_hyphen_values = true)]
+    #[arg(long, num_args = 1..=4, allow_hyphen_values = true)]
     pub offset: Option<Vec<String>>,
+    /// Create every entry in a range such as 2026-04-20..2026-04-24 or 2026-W17
+    #[arg(long, conflicts_with_all = ["date", "offset", "append"])]
+    pub range: Option<String>,
+    #[command(flatten)]
+    pub append: AppendArgs,
+}
+
+#[derive(Args)]
+pub struct AppendArgs {
+    /// Append a timestamped bullet instead of opening the note, `-` reads it from stdin
+    #[arg(long, value_name = "TEXT", allow_hyphen_values = true)]
+    pub append: Option<String>,
+    /// Append under this heading instead of at the end, e.g. "## Log"
+    #[arg(long, value_name = "HEADING", requires = "append")]
+    pub section: Option<String>,
+}
+
+#[derive(Args)]
+pub struct CaptureArgs {
+    /// Text to capture, read from stdin when omitted or `-`
+    pub text: Vec<String>,
+    /// Capture under this heading instead of the configured one, e.g. "## Ideas"
+    #[arg(long, value_name = "HEADING")]
+    pub section: Option<String>,
+    /// Open the inbox in the editor afterwards
+    #[arg(long)]
+    pub edit: bool,
+}
+
+#[derive(Args)]
+pub struct InsertArgs {
+    #[arg(index = 1)]
+    pub template: String,
+    /// Path or name of the note to insert into
+    #[arg(long, value_name = "NOTE")]
+    pub into: String,
+    #[command(flatten)]
+    pub position: InsertPositionArgs,
+}
+
+#[derive(Args)]
+#[group(multiple = false)]
+pub struct InsertPositionArgs {
+    /// Insert right below this heading, e.g. "## Meetings"
+    #[arg(long, value_name = "HEADING")]
+    pub after: Option<String>,
+    /// Insert above the line containing this marker, e.g. "<!-- zn:insert -->"
+    #[arg(long, value_name = "MARKER")]
+    pub at_marker: Option<String>,
+    /// Insert at the top, below any front matter
+    #[arg(long)]
+    pub top: bool,
+    /// Insert at the end of the note (default)
+    #[arg(long)]
+    pub bottom: bool,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("target").required(true).args(["note", "dangling"])))]
+pub struct LinksArgs {
+    /// Name or path of the note
+    pub note: Option<String>,
+    /// List links whose target doesn't exist across the note and journal folders
+    #[arg(long)]
+    pub dangling: bool,
+    /// Create the missing notes, skipping targets named like journal entries
+    #[arg(long, requires = "dangling")]
+    pub create: bool,
+    /// Template for the created notes, defaults to general.default_template
+    #[arg(long, requires = "create")]
+    pub template: Option<String>,
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct MvArgs {
+    /// Path or name of the note to move
+    pub from: String,
+    /// New name of the note, optionally in a folder of the note folder, e.g. "people/Ada"
+    pub to: String,
+    /// Show the changes as a diff without making them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct MigrateArgs {
+    #[command(subcommand)]
+    pub target: MigrateTarget,
+}
+
+#[derive(Subcommand)]
+pub enum MigrateTarget {
+    /// Rename every note to a case style and rewrite the links to them
+    Case(MigrateCaseArgs),
+}
+
+#[derive(Args)]
+pub struct MigrateCaseArgs {
+    #[arg(long, value_parser = ["camel", "kebab", "pascal", "snake", "original"])]
+    pub to: String,
+    /// Show the changes as a diff without making them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Args)]
+pub struct NoteTargetArgs {
+    /// Path or name of the note
+    pub note: String,
+}
+
+#[derive(Args)]
+#[command(group(ArgGroup::new("target").required(true).args(["note", "list"])))]
+pub struct RestoreArgs {
+    /// Path or name of the deleted or archived note
+    pub note: Option<String>,
+    /// List the notes in the trash that were deleted from the vault
+    #[arg(long)]
+    pub list: bool,
+    #[arg(long, requires = "list")]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct OpenArgs {
+    #[arg(required = true, num_args = 1..)]
+    pub query: Vec<String>,
+    /// Print the ranked matches instead of opening the best one
+    #[arg(long, conflicts_with = "pick")]
+    pub list: bool,
+    /// Print the ranked matches and open the one whose number is read from stdin
+    #[arg(long)]
+    pub pick: bool,
+    /// Number of matches to print
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+    #[arg(long, requires = "list")]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct SearchArgs {
+    pub query: String,
+    /// Treat the query as a regular expression
+    #[arg(short = 'e', long)]
+    pub regex: bool,
+    /// Ignore case, by default only lowercase queries do
+    #[arg(short, long, conflicts_with = "case_sensitive")]
+    pub ignore_case: bool,
+    /// Match case even for lowercase queries
+    #[arg(short = 's', long)]
+    pub case_sensitive: bool,
+    /// Only search the entries of this journal
+    #[arg(long)]
+    pub journal: Option<String>,
+    /// Only search notes created from this template
+    #[arg(long)]
+    pub template: Option<String>,
+    /// Only search notes from this range, e.g. 2026-04-01..2026-04-30 or 2026-W17
+    #[arg(long)]
+    pub range: Option<String>,
+    /// Lines of context around each match
+    #[arg(short = 'C', long, default_value_t = 0)]
+    pub context: usize,
+    /// Open the file of the Nth match in the editor
+    #[arg(
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub open: Option<usize>,
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct TagsArgs {
+    /// List the notes with this tag or one nested in it instead
+    pub tag: Option<String>,
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct StatsArgs {
+    /// Only count notes from this date on, e.g. 2026-04-01, 2026-04 or 30 days ago
+    #[arg(long)]
+    pub since: Option<String>,
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args)]
+pub struct IndexArgs {
+    #[command(subcommand)]
+    pub action: IndexAction,
+}
+
+#[derive(Subcommand)]
+pub enum IndexAction {
+    /// Index every note from scratch
+    Rebuild,
+    /// Show the size of the index and the changes it hasn't picked up yet
+    Status(ListOutputArgs),
+}
+
+#[derive(Args)]
+pub struct DoctorArgs {
+    #[command(subcommand)]
+    pub check: DoctorCheck,
+}
+
+#[derive(Subcommand)]
+pub enum DoctorCheck {
+    /// List notes whose names only differ in case style or accents
+    Duplicates(ListOutputArgs),
 }
 
 #[derive(Args)]
@@ -82,6 +348,40 @@ pub struct ListOutputArgs {
     pub json: bool,
 }
 
+impl AppendArgs {
+    pub fn to_append(&self) -> std::io::Result<Option<Append>> {
+        self.append
+            .as_deref()
+            .map(|text| Append::new(text, self.section.as_deref()))
+            .transpose()
+    }
+}
+
+impl CaptureArgs {
+    pub fn to_append(&self) -> std::io::Result<Append> {
+        let text = match self.text.join(" ") {
+            text if text.is_empty() => String::from("-"),
+            text => text,
+        };
+
+        Append::new(&text, self.section.as_deref())
+    }
+}
+
+impl InsertPositionArgs {
+    pub fn position(&self) -> InsertPosition {
+        if let Some(heading) = &self.after {
+            InsertPosition::AfterHeading(heading.clone())
+        } else if let Some(marker) = &self.at_marker {
+            InsertPosition::AtMarker(marker.clone())
+        } else if self.top {
+            InsertPosition::Top
+        } else {
+            InsertPosition::Bottom
+        }
+    }
+}
+
 impl JournalArgs {
     pub fn offset_value(&self) -> Option<String> {
         self.offset.as_ref().map(|parts| parts.join(" "))
@@ -103,6 +403,27 @@ pub fn build_cli() -> Command {
 
     let mut command = Cli::command();
 
+    command = command.mut_subcommand("insert", |cmd| {
+        cmd.mut_arg("template", |arg| {
+            arg.add(ArgValueCompleter::new(complete_note_templates))
+        })
+    });
+
+    command = command.mut_subcommand("links", |cmd| {
+        cmd.mut_arg("template", |arg| {
+            arg.add(ArgValueCompleter::new(complete_note_templates))
+        })
+    });
+
+    command = command.mut_subcommand("search", |cmd| {
+        cmd.mut_arg("journal", |arg| {
+            arg.add(ArgValueCompleter::new(complete_journal_names))
+        })
+        .mut_arg("template", |arg| {
+            arg.add(ArgValueCompleter::new(complete_note_templates))
+        })
+    });
+
     command = command.mut_subcommand("note", |cmd| {
         cmd.mut_arg("template", |arg| {
             let arg = arg.add(ArgValueCompleter::new(complete_note_templates));
@@ -235,6 +556,215 @@ mod tests {
         assert_eq!(args.offset_value().as_deref(), Some("+1 day"));
     }
 
+    #[test]
+    fn parses_quoted_negative_offset() {
+        let cli = Cli::try_parse_from([
+            "zn",
+            "journal",
+            "day",
+            "--date",
+            "today",
+            "--offset",
+            "-1 week +2 days",
+        ])
+        .unwrap();
+
+        let SubCommand::Journal(args) = cli.subcommand else {
+            panic!("expected journal subcommand");
+        };
+
+        assert_eq!(args.offset_value().as_deref(), Some("-1 week +2 days"));
+        assert_eq!(args.date.as_deref(), Some("today"));
+    }
+
+    #[test]
+    fn parses_unquoted_compound_offset_before_other_flags() {
+        let cli = Cli::try_parse_from([
+            "zn", "journal", "day", "--offset", "+1", "week", "-2", "days", "--date", "today",
+        ])
+        .unwrap();
+
+        let SubCommand::Journal(args) = cli.subcommand else {
+            panic!("expected journal subcommand");
+        };
+
+        assert_eq!(args.offset_value().as_deref(), Some("+1 week -2 days"));
+        assert_eq!(args.date.as_deref(), Some("today"));
+    }
+
+    #[test]
+    fn parses_append_with_section_and_stdin_marker() {
+        let cli = Cli::try_parse_from([
+            "zn",
+            "journal",
+            "day",
+            "--append",
+            "-",
+            "--section",
+            "## Log",
+        ])
+        .unwrap();
+
+        let SubCommand::Journal(args) = cli.subcommand else {
+            panic!("expected journal subcommand");
+        };
+
+        assert_eq!(args.append.append.as_deref(), Some("-"));
+        assert_eq!(args.append.section.as_deref(), Some("## Log"));
+    }
+
+    #[test]
+    fn rejects_section_without_append() {
+        let result = Cli::try_parse_from(["zn", "journal", "day", "--section", "## Log"]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parses_unquoted_capture_text() {
+        let cli =
+            Cli::try_parse_from(["zn", "capture", "call", "-", "the", "bank", "--edit"]).unwrap();
+
+        let SubCommand::Capture(args) = cli.subcommand else {
+            panic!("expected capture subcommand");
+        };
+
+        assert_eq!(args.text.join(" "), "call - the bank");
+        assert!(args.edit);
+    }
+
+    #[test]
+    fn parses_insert_position() {
+        let cli = Cli::try_parse_from([
+            "zn",
+            "insert",
+            "meeting",
+            "--into",
+            "Project X",
+            "--after",
+            "## Meetings",
+        ])
+        .unwrap();
+
+        let SubCommand::Insert(args) = cli.subcommand else {
+            panic!("expected insert subcommand");
+        };
+
+        assert_eq!(
+            args.position.position(),
+            InsertPosition::AfterHeading("## Meetings".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_insert_positions() {
+        let result = Cli::try_parse_from([
+            "zn",
+            "insert",
+            "meeting",
+            "--into",
+            "Project X",
+            "--top",
+            "--bottom",
+        ]);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn links_requires_a_note_or_dangling() {
+        assert!(Cli::try_parse_from(["zn", "links"]).is_err());
+        assert!(Cli::try_parse_from(["zn", "links", "Ada", "--dangling"]).is_err());
+        assert!(Cli::try_parse_from(["zn", "links", "--dangling", "--create"]).is_ok());
+    }
+
+    #[test]
+    fn search_open_defaults_to_first_hit() {
+        let cli = Cli::try_parse_from(["zn", "search", "todo", "--open"]).unwrap();
+
+        let SubCommand::Search(args) = cli.subcommand else {
+            panic!("expected search subcommand");
+        };
+
+        assert_eq!(args.open, Some(1));
+        assert!(Cli::try_parse_from(["zn", "search", "todo", "--open", "0"]).is_err());
+    }
+
+    #[test]
+    fn parses_note_tags() {
+        let cli = Cli::try_parse_from([
+            "zn", "note", "meeting", "Kickoff", "--tag", "work", "--tag", "q2",
+        ])
+        .unwrap();
+
+        let SubCommand::Note(args) = cli.subcommand else {
+            panic!("expected note subcommand");
+        };
+
+        assert_eq!(args.name, vec!["Kickoff"]);
+        assert_eq!(args.tags, vec!["work", "q2"]);
+    }
+
+    #[test]
+    fn parses_mv_dry_run() {
+        let cli =
+            Cli::try_parse_from(["zn", "mv", "Old Name", "people/New Name", "--dry-run"]).unwrap();
+
+        let SubCommand::Mv(args) = cli.subcommand else {
+            panic!("expected mv subcommand");
+        };
+
+        assert_eq!(args.from, "Old Name");
+        assert_eq!(args.to, "people/New Name");
+        assert!(args.dry_run);
+    }
+
+    #[test]
+    fn parses_stats_since() {
+        let cli = Cli::try_parse_from(["zn", "stats", "--since", "2026-04", "--json"]).unwrap();
+
+        let SubCommand::Stats(args) = cli.subcommand else {
+            panic!("expected stats subcommand");
+        };
+
+        assert_eq!(args.since.as_deref(), Some("2026-04"));
+        assert!(args.json);
+    }
+
+    #[test]
+    fn migrate_case_accepts_case_styles_only() {
+        let cli =
+            Cli::try_parse_from(["zn", "migrate", "case", "--to", "kebab", "--dry-run"]).unwrap();
+
+        let SubCommand::Migrate(args) = cli.subcommand else {
+            panic!("expected migrate subcommand");
+        };
+        let MigrateTarget::Case(args) = args.target;
+
+        assert_eq!(args.to, "kebab");
+        assert!(args.dry_run);
+        assert!(Cli::try_parse_from(["zn", "migrate", "case", "--to", "upper"]).is_err());
+    }
+
+    #[test]
+    fn restore_requires_a_note_or_list() {
+        assert!(Cli::try_parse_from(["zn", "restore"]).is_err());
+        assert!(Cli::try_parse_from(["zn", "restore", "Ada", "--list"]).is_err());
+        assert!(Cli::try_parse_from(["zn", "restore", "--list", "--json"]).is_ok());
+    }
+
+    #[test]
+    fn parses_doctor_duplicates_json() {
+        let cli = Cli::try_parse_from(["zn", "doctor", "duplicates", "--json"]).unwrap();
+
+        let SubCommand::Doctor(args) = cli.subcommand else {
+            panic!("expected doctor subcommand");
+        };
+        let DoctorCheck::Duplicates(output) = args.check;
+
+        assert!(output.json);
+    }
+
     #[test]
     fn parses_completion_subcommand() {
         let cli = Cli::try_parse_from(["zn", "completion", "bash"]).unwrap();
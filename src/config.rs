@@ -13,6 +13,7 @@ use std::{
 pub struct Config {
     pub general: GeneralConfig,
     pub journal: Option<Vec<JournalConfig>>,
+    pub capture: Option<CaptureConfig>,
 }
 
 #[derive(Deserialize)]
@@ -22,6 +23,49 @@ pub struct GeneralConfig {
     pub note_folder_path: String,
     pub journal_folder_path: String,
     pub note_case_style: Option<CaseStyle>,
+    pub default_template: Option<String>,
+    pub duplicate_notes: Option<DuplicateNotes>,
+    pub archive_folder_path: Option<String>,
+    pub trash_folder_path: Option<String>,
+    pub front_matter: Option<FrontMatterFormat>,
+    pub front_matter_fields: Option<Vec<String>>,
+    pub week_start: Option<WeekStart>,
+    pub quarter_style: Option<QuarterStyle>,
+    pub fiscal_year_start_month: Option<u32>,
+    pub locale: Option<String>,
+    pub holidays_path: Option<String>,
+    pub timezone: Option<String>,
+    pub day_starts_at: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WeekStart {
+    Monday,
+    Sunday,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuarterStyle {
+    Calendar,
+    IsoWeek,
+}
+
+/// What `zn note` does when the name only matches an existing note after normalization.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateNotes {
+    #[default]
+    Open,
+    Create,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontMatterFormat {
+    Yaml,
+    Toml,
 }
 
 #[derive(Deserialize)]
@@ -40,6 +84,66 @@ pub struct JournalConfig {
     pub format: String,
     pub template: String,
     pub folder_path: String,
+    pub period: Option<JournalPeriod>,
+    pub day_starts_at: Option<String>,
+    pub carry_over_sections: Option<Vec<String>>,
+    pub carry_over_migrate: Option<bool>,
+    pub tags: Option<Vec<String>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CaptureConfig {
+    pub inbox: Option<String>,
+    pub journal: Option<String>,
+    pub template: Option<String>,
+    pub section: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JournalPeriod {
+    Day,
+    Week,
+    Month,
+    Quarter,
+    #[serde(rename = "half-year")]
+    HalfYear,
+    Year,
+}
+
+impl JournalPeriod {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            "quarter" => Some(Self::Quarter),
+            "half-year" => Some(Self::HalfYear),
+            "year" => Some(Self::Year),
+            _ => None,
+        }
+    }
+}
+
+impl CaseStyle {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "camel" => Some(Self::Camel),
+            "kebab" => Some(Self::Kebab),
+            "pascal" => Some(Self::Pascal),
+            "snake" => Some(Self::Snake),
+            "original" => Some(Self::Original),
+            _ => None,
+        }
+    }
+}
+
+impl JournalConfig {
+    /// The configured period, or the one implied by the filename format.
+    pub fn period(&self) -> JournalPeriod {
+        self.period
+            .unwrap_or_else(|| crate::utils::date::infer_period(&self.format))
+    }
 }
 
 pub enum Sub {
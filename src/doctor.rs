@@ -0,0 +1,32 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::utils::{casing::normalized_name, index::NoteIndex, vault::regular_notes};
+
+/// Notes whose names only differ in case style or accents, e.g. `My Note.md` and `my-note.md`.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct DuplicateGroup {
+    pub key: String,
+    pub paths: Vec<String>,
+}
+
+/// Groups the notes of the note folder that normalize to the same name.
+pub fn handle_doctor_duplicates_command() -> Result<Vec<DuplicateGroup>, Box<dyn std::error::Error>>
+{
+    let index = NoteIndex::open()?;
+
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for note in regular_notes(&index)? {
+        groups
+            .entry(normalized_name(&note.name))
+            .or_default()
+            .push(note.path.clone());
+    }
+
+    Ok(groups
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(key, paths)| DuplicateGroup { key, paths })
+        .collect())
+}
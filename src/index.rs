@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::utils::index::{index_path, vault_files, IndexChanges, NoteIndex};
+
+#[derive(Serialize)]
+pub struct IndexStatus {
+    pub path: String,
+    pub notes: usize,
+    pub updated_at: Option<DateTime<Utc>>,
+    /// What the next command using the index will pick up.
+    pub pending: IndexChanges,
+}
+
+/// Indexes every note from scratch, returning where the index was written and its size.
+pub fn handle_index_rebuild_command() -> Result<(String, usize), Box<dyn std::error::Error>> {
+    let index = NoteIndex::rebuild()?;
+    Ok((
+        index_path()?.to_string_lossy().into_owned(),
+        index.notes.len(),
+    ))
+}
+
+/// Describes the stored index without updating it.
+pub fn handle_index_status_command() -> Result<IndexStatus, Box<dyn std::error::Error>> {
+    let path = index_path()?;
+    let stored = NoteIndex::load(&path);
+    let (notes, updated_at) = (stored.notes.len(), stored.updated_at);
+
+    let mut refreshed = stored;
+    let pending = refreshed.refresh(&vault_files()?);
+
+    Ok(IndexStatus {
+        path: path.to_string_lossy().into_owned(),
+        notes,
+        updated_at: path.exists().then_some(updated_at).flatten(),
+        pending,
+    })
+}
@@ -0,0 +1,52 @@
+use std::{fs, io, path::Path};
+
+use crate::utils::{
+    backup_file,
+    clock::Clock,
+    date::Calendar,
+    frontmatter::{append_front_matter, split_front_matter},
+    markdown::{insert_block, InsertPosition},
+    open_path_in_editor,
+    placeholder::TemplateContext,
+    resolve_note_path,
+    template::{check_template, template_file_contents},
+    write_atomically,
+};
+
+/// Renders `template` into an existing note, keeping a `.bak` copy of the previous contents. The
+/// front matter of the template is merged into the one of the note, whose own keys win, except
+/// for `title` and `aliases`, which belong to the note alone.
+pub fn handle_insert_command(
+    template: &str,
+    into: &str,
+    position: &InsertPosition,
+) -> Result<String, Box<dyn std::error::Error>> {
+    check_template(template)?;
+
+    let path = resolve_note_path(into)?;
+    let title = Path::new(&path)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let clock = Clock::load()?;
+    let context = TemplateContext::new(title, clock.today())
+        .with_calendar(Calendar::load()?)
+        .with_now(clock.now);
+    let (mut fields, format, block) =
+        split_front_matter(&template_file_contents(template.to_owned(), &context)?);
+    fields.retain(|(name, _)| name != "title" && name != "aliases");
+
+    let mut contents = fs::read_to_string(&path)?;
+    if let Some(format) = format {
+        contents = append_front_matter(&contents, &fields, format);
+    }
+    let updated = insert_block(&contents, position, &block)
+        .map_err(|err| io::Error::other(format!("can't insert into '{path}': {err}")))?;
+
+    backup_file(&path)?;
+    write_atomically(&path, &updated)?;
+
+    open_path_in_editor(&path)?;
+    Ok(path)
+}
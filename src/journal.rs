@@ -1,19 +1,80 @@
-use crate::utils::{
-    date::resolve_reference_date,
-    template::{check_template, insert_template_journal, specific_template_info},
+use crate::{
+    config::JournalConfig,
+    utils::{
+        capture::{append_entry, Append},
+        clock::Clock,
+        date::{
+            format_date, parse_date_range, period_starts_between, resolve_reference_date, Calendar,
+        },
+        open_paths_in_editor,
+        placeholder::TemplateContext,
+        template::{
+            check_template, create_journal_entry, insert_template_journal, specific_template_info,
+        },
+    },
 };
 
 pub fn handle_journal_command(
     name: &str,
     date: Option<&str>,
     offset: Option<&str>,
+    append: Option<&Append>,
 ) -> Result<String, Box<dyn std::error::Error>> {
     let journal = specific_template_info(name)?;
 
     check_template(&journal.template)?;
 
-    let reference_date = resolve_reference_date(date, offset)
-        .map_err(|err| std::io::Error::other(err.to_string()))?;
+    let calendar = Calendar::load()?.for_format(&journal.format);
+    let clock = journal_clock(&journal)?;
+    let reference_date =
+        resolve_reference_date(date, offset, journal.period(), &calendar, clock.today())
+            .map_err(|err| std::io::Error::other(err.to_string()))?;
 
-    insert_template_journal(&journal, reference_date)
+    let Some(append) = append else {
+        return insert_template_journal(&journal, reference_date, &calendar, &clock);
+    };
+
+    let path = create_journal_entry(&journal, reference_date, &calendar, &clock)?;
+    let title =
+        format_date(reference_date, &journal.format, &calendar).map_err(std::io::Error::other)?;
+    let context = TemplateContext::new(title, reference_date)
+        .with_period(journal.period())
+        .with_calendar(calendar)
+        .with_now(clock.now)
+        .with_journal(journal.clone());
+
+    append_entry(&path, append, context)?;
+    Ok(path)
+}
+
+/// Creates every missing entry of the journal within `range` and opens them all at once.
+pub fn handle_journal_range_command(
+    name: &str,
+    range: &str,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let journal = specific_template_info(name)?;
+
+    check_template(&journal.template)?;
+
+    let calendar = Calendar::load()?.for_format(&journal.format);
+    let clock = journal_clock(&journal)?;
+    let (start, end) =
+        parse_date_range(range, clock.today(), &calendar).map_err(std::io::Error::other)?;
+
+    let mut paths: Vec<String> = Vec::new();
+    for date in period_starts_between(start, end, journal.period(), &calendar) {
+        let path = create_journal_entry(&journal, date, &calendar, &clock)?;
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+
+    open_paths_in_editor(&paths)?;
+    Ok(paths)
+}
+
+/// The clock for a journal, whose own `day_starts_at` takes precedence over the general one.
+fn journal_clock(journal: &JournalConfig) -> Result<Clock, Box<dyn std::error::Error>> {
+    let clock = Clock::load()?.with_day_start(journal.day_starts_at.as_deref())?;
+    Ok(clock)
 }
@@ -0,0 +1,132 @@
+use std::{io, path::Path};
+
+use serde::Serialize;
+
+use crate::{
+    config::{Config, Sub},
+    utils::{
+        casing::convert_case,
+        check_note_name,
+        clock::Clock,
+        date::{parse_formatted_dates, Calendar},
+        links::{note_file, LinkIndex, NoteFile, SourcedLink},
+        template::{check_template, create_note_from_template, NoteOptions},
+    },
+};
+
+#[derive(Serialize)]
+pub struct NoteLinks {
+    pub note: NoteFile,
+    pub outgoing: Vec<OutgoingLink>,
+    pub incoming: Vec<SourcedLink>,
+}
+
+#[derive(Serialize)]
+pub struct OutgoingLink {
+    #[serde(flatten)]
+    pub link: SourcedLink,
+    /// The note the link resolves to, `None` when it dangles.
+    pub resolved: Option<NoteFile>,
+}
+
+/// The links written in `note` and the ones pointing at it from other notes.
+pub fn handle_links_command(note: &str) -> Result<NoteLinks, Box<dyn std::error::Error>> {
+    let index = LinkIndex::build()?;
+
+    let note = if Path::new(note).is_file() {
+        note_file(Path::new(note))
+    } else {
+        index.resolve(note.trim_end_matches(".md")).cloned()
+    }
+    .ok_or_else(|| io::Error::other(format!("note '{note}' not found")))?;
+
+    let outgoing = index
+        .outgoing(&note)
+        .into_iter()
+        .map(|link| OutgoingLink {
+            resolved: index.resolve(&link.link.name).cloned(),
+            link: link.clone(),
+        })
+        .collect();
+    let incoming = index
+        .backlinks(&[&note.name])
+        .into_iter()
+        .cloned()
+        .collect();
+
+    Ok(NoteLinks {
+        note,
+        outgoing,
+        incoming,
+    })
+}
+
+/// Every link across the note and journal folders whose target doesn't exist.
+pub fn handle_dangling_links_command() -> Result<Vec<SourcedLink>, Box<dyn std::error::Error>> {
+    let index = LinkIndex::build()?;
+    Ok(index.dangling().into_iter().cloned().collect())
+}
+
+/// Creates a note for every dangling target, except the ones named like a journal entry.
+pub fn create_dangling_notes(
+    links: &[SourcedLink],
+    template: Option<&str>,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let template = match template {
+        Some(template) => template.to_owned(),
+        None => Config::read()?.general.default_template.ok_or_else(|| {
+            io::Error::other(
+                "no template to create notes from. pass --template or set general.default_template",
+            )
+        })?,
+    };
+    check_template(&template)?;
+
+    let mut names: Vec<&str> = links.iter().map(|link| link.link.name.as_str()).collect();
+    names.sort();
+    names.dedup();
+
+    let journal_names = journal_entry_names(&names)?;
+    let mut created = Vec::new();
+
+    for name in names {
+        if journal_names.contains(&name) {
+            continue;
+        }
+
+        let note_name = convert_case(name.to_owned());
+        if check_note_name(&note_name, Sub::Note)?.is_some() {
+            continue;
+        }
+
+        created.push(create_note_from_template(
+            template.clone(),
+            note_name,
+            Sub::Note,
+            &NoteOptions::default(),
+        )?);
+    }
+
+    Ok(created)
+}
+
+/// The names that match the filename format of a configured journal.
+fn journal_entry_names<'a>(names: &[&'a str]) -> Result<Vec<&'a str>, Box<dyn std::error::Error>> {
+    let calendar = Calendar::load()?;
+    let today = Clock::load()?.today();
+    let mut journal_names = Vec::new();
+
+    for journal in Config::read()?.journal.unwrap_or_default() {
+        let dates = parse_formatted_dates(
+            names,
+            &journal.format,
+            journal.period(),
+            &calendar.for_format(&journal.format),
+            today,
+        )
+        .map_err(io::Error::other)?;
+        journal_names.extend(dates.into_iter().map(|(name, _)| name));
+    }
+
+    Ok(journal_names)
+}
@@ -1,17 +1,43 @@
-use crate::cli::{parse_cli, ListTarget, SubCommand};
+use crate::archive::*;
+use crate::capture::*;
+use crate::cli::{parse_cli, DoctorCheck, IndexAction, ListTarget, MigrateTarget, SubCommand};
+use crate::doctor::*;
+use crate::index::*;
+use crate::insert::*;
 use crate::journal::*;
+use crate::links::*;
+use crate::migrate::*;
+use crate::mv::*;
 use crate::note::*;
+use crate::open::*;
+use crate::search::*;
+use crate::stats::*;
+use crate::tags::*;
 use clap_complete::aot::generate;
-use config::Config;
+use config::{CaseStyle, Config};
 use std::{env, io, process};
 use utils::casing::convert_case;
-use utils::template::{journal_entries, template_entries};
+use utils::open_path_in_editor;
+use utils::rename::render_diff;
+use utils::template::{journal_entries, template_entries, NoteOptions};
 
+mod archive;
+mod capture;
 mod cli;
 mod config;
+mod doctor;
 mod errors;
+mod index;
+mod insert;
 mod journal;
+mod links;
+mod migrate;
+mod mv;
 mod note;
+mod open;
+mod search;
+mod stats;
+mod tags;
 mod utils;
 
 fn main() {
@@ -47,23 +73,304 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
         env::set_var("ZAPNOTE_NO_EDITOR", String::from("false"));
     }
 
+    if let Some(tz) = &cli.tz {
+        env::set_var("ZAPNOTE_TZ", tz);
+    }
+
+    if let Some(now) = &cli.now {
+        env::set_var("ZAPNOTE_NOW", now);
+    }
+
     match &cli.subcommand {
         SubCommand::Note(args) => {
             let note_name = args.name.join(" ");
-            let case_converted_title = convert_case(note_name);
+            let case_converted_title = convert_case(note_name.clone());
+
+            let mut options = NoteOptions {
+                tags: args.tags.clone(),
+                aliases: args.aliases.clone(),
+            };
+            if case_converted_title != note_name && !options.aliases.contains(&note_name) {
+                options.aliases.insert(0, note_name);
+            }
 
-            let path = handle_note_command(&args.template, case_converted_title)?;
+            let append = args.append.to_append()?;
+            let path = handle_note_command(
+                &args.template,
+                case_converted_title,
+                &options,
+                append.as_ref(),
+            )?;
             if cli.no_editor {
                 println!("{path}");
             }
         }
         SubCommand::Journal(args) => {
-            let offset = args.offset_value();
-            let path = handle_journal_command(&args.name, args.date.as_deref(), offset.as_deref())?;
+            let paths = match &args.range {
+                Some(range) => handle_journal_range_command(&args.name, range)?,
+                None => {
+                    let offset = args.offset_value();
+                    let append = args.append.to_append()?;
+                    let path = handle_journal_command(
+                        &args.name,
+                        args.date.as_deref(),
+                        offset.as_deref(),
+                        append.as_ref(),
+                    )?;
+                    vec![path]
+                }
+            };
+            if cli.no_editor {
+                paths.iter().for_each(|path| println!("{path}"));
+            }
+        }
+        SubCommand::Capture(args) => {
+            let path = handle_capture_command(args.to_append()?, args.edit)?;
             if cli.no_editor {
                 println!("{path}");
             }
         }
+        SubCommand::Insert(args) => {
+            let position = args.position.position();
+            let path = handle_insert_command(&args.template, &args.into, &position)?;
+            if cli.no_editor {
+                println!("{path}");
+            }
+        }
+        SubCommand::Links(args) if args.dangling => {
+            let links = handle_dangling_links_command()?;
+            if args.create {
+                let paths = create_dangling_notes(&links, args.template.as_deref())?;
+                paths.iter().for_each(|path| println!("{path}"));
+            } else if args.json {
+                println!("{}", serde_json::to_string_pretty(&links)?);
+            } else {
+                links.iter().for_each(|link| {
+                    println!(
+                        "{}:{}: [[{}]]",
+                        link.source.path, link.link.line, link.link.name
+                    )
+                });
+            }
+        }
+        SubCommand::Links(args) => {
+            let note = args.note.as_deref().unwrap_or_default();
+            let links = handle_links_command(note)?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&links)?);
+            } else {
+                println!("outgoing:");
+                links.outgoing.iter().for_each(|outgoing| {
+                    let target = outgoing
+                        .resolved
+                        .as_ref()
+                        .map_or("(missing)", |note| note.path.as_str());
+                    println!("  {} -> {target}", outgoing.link.link.name);
+                });
+                println!("incoming:");
+                links
+                    .incoming
+                    .iter()
+                    .for_each(|link| println!("  {}:{}", link.source.path, link.link.line));
+            }
+        }
+        SubCommand::Mv(args) => {
+            let (rename, changes) = handle_mv_command(&args.from, &args.to, args.dry_run)?;
+            if args.dry_run {
+                println!("{}", render_diff(&changes));
+            } else {
+                let updated = changes
+                    .iter()
+                    .filter(|change| change.new_path.is_none())
+                    .count();
+                println!("{} -> {}", rename.from.display(), rename.to.display());
+                println!("updated links in {updated} notes");
+            }
+        }
+        SubCommand::Migrate(args) => match &args.target {
+            MigrateTarget::Case(args) => {
+                let case = CaseStyle::from_name(&args.to).ok_or("unknown case style")?;
+                let (renames, changes) = handle_migrate_case_command(&case, args.dry_run)?;
+                if args.dry_run {
+                    println!("{}", render_diff(&changes));
+                } else {
+                    let updated = changes
+                        .iter()
+                        .filter(|change| change.new_path.is_none())
+                        .count();
+                    println!(
+                        "renamed {} notes, updated links in {updated} notes",
+                        renames.len()
+                    );
+                }
+            }
+        },
+        SubCommand::Archive(args) => print_moved_note(&handle_archive_command(&args.note)?),
+        SubCommand::Rm(args) => print_moved_note(&handle_rm_command(&args.note)?),
+        SubCommand::Restore(args) => match &args.note {
+            Some(note) => print_moved_note(&handle_restore_command(note)?),
+            None => {
+                let trashed = handle_restore_list_command()?;
+                if args.json {
+                    println!("{}", serde_json::to_string_pretty(&trashed)?);
+                } else {
+                    trashed
+                        .iter()
+                        .for_each(|note| println!("{}  {}", note.deleted_at, note.path));
+                }
+            }
+        },
+        SubCommand::Open(args) => {
+            let mut ranked = handle_open_command(&args.query.join(" "))?;
+            ranked.truncate(args.limit);
+
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&ranked)?);
+                return Ok(());
+            }
+
+            if args.list || args.pick {
+                ranked.iter().enumerate().for_each(|(index, ranked)| {
+                    println!("{:>2}. {}  {}", index + 1, ranked.title, ranked.note.path)
+                });
+                if args.list {
+                    return Ok(());
+                }
+            }
+
+            let choice = if args.pick {
+                read_choice(ranked.len())?
+            } else {
+                1
+            };
+            let chosen = ranked
+                .get(choice - 1)
+                .ok_or_else(|| format!("no note matches '{}'", args.query.join(" ")))?;
+
+            open_path_in_editor(&chosen.note.path)?;
+            if cli.no_editor {
+                println!("{}", chosen.note.path);
+            }
+        }
+        SubCommand::Search(args) => {
+            let hits = handle_search_command(args)?;
+            if let Some(number) = args.open {
+                let hit = hits
+                    .get(number - 1)
+                    .ok_or_else(|| format!("no match number {number}, found {}", hits.len()))?;
+                open_path_in_editor(&hit.note.path)?;
+            } else if args.json {
+                println!("{}", serde_json::to_string_pretty(&hits)?);
+            } else {
+                for (index, hit) in hits.iter().enumerate() {
+                    if args.context > 0 && index > 0 {
+                        println!("--");
+                    }
+                    let first_line = hit.line - hit.before.len();
+                    hit.before.iter().enumerate().for_each(|(offset, line)| {
+                        println!("{}-{}-{line}", hit.note.path, first_line + offset)
+                    });
+                    println!("{}:{}:{}", hit.note.path, hit.line, hit.text);
+                    hit.after.iter().enumerate().for_each(|(offset, line)| {
+                        println!("{}-{}-{line}", hit.note.path, hit.line + 1 + offset)
+                    });
+                }
+            }
+        }
+        SubCommand::Tags(args) => match &args.tag {
+            Some(tag) => {
+                let notes = handle_tag_notes_command(tag)?;
+                if args.json {
+                    println!("{}", serde_json::to_string_pretty(&notes)?);
+                } else {
+                    notes.iter().for_each(|note| println!("{}", note.path));
+                }
+            }
+            None => {
+                let counts = handle_tags_command()?;
+                if args.json {
+                    println!("{}", serde_json::to_string_pretty(&counts)?);
+                } else {
+                    counts
+                        .iter()
+                        .for_each(|count| println!("{:>4}  {}", count.count, count.tag));
+                }
+            }
+        },
+        SubCommand::Stats(args) => {
+            let stats = handle_stats_command(args.since.as_deref())?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                println!("notes: {}", stats.notes);
+                println!("journal entries: {}", stats.journal_entries);
+                println!("words: {}", stats.words);
+                println!();
+                println!("templates:");
+                stats
+                    .templates
+                    .iter()
+                    .for_each(|(template, count)| println!("{count:>6}  {template}"));
+                println!("{:>6}  (none)", stats.without_template);
+                for journal in &stats.journals {
+                    println!();
+                    println!("{}:", journal.name);
+                    println!("  entries: {}", journal.entries);
+                    println!("  words: {}", journal.words);
+                    println!(
+                        "  streak: {} current, {} longest",
+                        journal.current_streak, journal.longest_streak
+                    );
+                    journal
+                        .entries_per_month
+                        .iter()
+                        .for_each(|(month, count)| println!("  {month}  {count:>4}"));
+                }
+            }
+        }
+        SubCommand::Index(args) => match &args.action {
+            IndexAction::Rebuild => {
+                let (path, notes) = handle_index_rebuild_command()?;
+                println!("indexed {notes} notes in {path}");
+            }
+            IndexAction::Status(output) => {
+                let status = handle_index_status_command()?;
+                if output.json {
+                    println!("{}", serde_json::to_string_pretty(&status)?);
+                } else {
+                    let updated_at = status
+                        .updated_at
+                        .map_or(String::from("never"), |updated_at| updated_at.to_rfc3339());
+                    println!("index: {}", status.path);
+                    println!("notes: {}", status.notes);
+                    println!("updated: {updated_at}");
+                    println!(
+                        "pending: {} added, {} updated, {} removed",
+                        status.pending.added, status.pending.updated, status.pending.removed
+                    );
+                }
+            }
+        },
+        SubCommand::Doctor(args) => match &args.check {
+            DoctorCheck::Duplicates(output) => {
+                let groups = handle_doctor_duplicates_command()?;
+                if output.json {
+                    println!("{}", serde_json::to_string_pretty(&groups)?);
+                } else if groups.is_empty() {
+                    println!("no duplicate notes");
+                } else {
+                    for (index, group) in groups.iter().enumerate() {
+                        if index > 0 {
+                            println!();
+                        }
+                        println!("{}:", group.key);
+                        for path in &group.paths {
+                            println!("  {path}");
+                        }
+                    }
+                }
+            }
+        },
         SubCommand::Completion(args) => {
             let mut cmd = cli::build_cli();
             let command_name = cmd.get_name().to_string();
@@ -93,3 +400,35 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// Prints where a note went, warning about the notes whose links to it now point elsewhere.
+fn print_moved_note(moved: &MovedNote) {
+    if !moved.linked_from.is_empty() {
+        eprintln!(
+            "warning: {} notes still link to {}:",
+            moved.linked_from.len(),
+            moved.from
+        );
+        moved
+            .linked_from
+            .iter()
+            .for_each(|path| eprintln!("  {path}"));
+    }
+    println!("{} -> {}", moved.from, moved.to);
+}
+
+/// Reads the number of a listed match from stdin.
+fn read_choice(count: usize) -> Result<usize, Box<dyn std::error::Error>> {
+    if count == 0 {
+        return Err("no matching notes".into());
+    }
+
+    eprint!("open [1-{count}]: ");
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    match input.trim().parse::<usize>() {
+        Ok(choice) if (1..=count).contains(&choice) => Ok(choice),
+        _ => Err(format!("invalid choice '{}', expected 1 to {count}", input.trim()).into()),
+    }
+}
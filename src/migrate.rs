@@ -0,0 +1,60 @@
+use std::{collections::BTreeMap, io, path::PathBuf};
+
+use crate::{
+    config::CaseStyle,
+    utils::{
+        casing::convert_case_to,
+        index::NoteIndex,
+        rename::{apply_changes, plan_renames, FileChange, NoteRename},
+        vault::regular_notes,
+    },
+};
+
+/// Renames every note of the note folder to `case`, rewriting the links to the renamed notes,
+/// or only plans the changes with `dry_run`. Nothing is touched when two notes would end up with
+/// the same file name.
+pub fn handle_migrate_case_command(
+    case: &CaseStyle,
+    dry_run: bool,
+) -> Result<(Vec<NoteRename>, Vec<FileChange>), Box<dyn std::error::Error>> {
+    let index = NoteIndex::open()?;
+    let notes = regular_notes(&index)?;
+
+    let renames: Vec<NoteRename> = notes
+        .iter()
+        .filter_map(|note| {
+            let from = PathBuf::from(&note.path);
+            let name = convert_case_to(note.name.clone(), case);
+            let to = from.with_file_name(format!("{name}.md"));
+            (to != from).then_some(NoteRename { from, to })
+        })
+        .collect();
+
+    let mut targets: BTreeMap<&PathBuf, Vec<String>> = BTreeMap::new();
+    for rename in &renames {
+        targets
+            .entry(&rename.to)
+            .or_default()
+            .push(rename.from.display().to_string());
+    }
+
+    let collisions: Vec<String> = targets
+        .iter()
+        .filter(|(target, sources)| sources.len() > 1 || target.exists())
+        .map(|(target, sources)| format!("  {} <- {}", target.display(), sources.join(", ")))
+        .collect();
+    if !collisions.is_empty() {
+        return Err(io::Error::other(format!(
+            "no note was renamed, these would collide:\n{}",
+            collisions.join("\n")
+        ))
+        .into());
+    }
+
+    let changes = plan_renames(&index, &renames)?;
+    if !dry_run {
+        apply_changes(&changes)?;
+    }
+
+    Ok((renames, changes))
+}
@@ -0,0 +1,85 @@
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    config::Sub,
+    utils::{
+        casing::{convert_case, normalized_name},
+        command_folder_path,
+        index::NoteIndex,
+        rename::{apply_changes, plan_renames, FileChange, NoteRename},
+        resolve_note_path,
+    },
+};
+
+/// Moves a note within the note folder and rewrites the links pointing to it, or only plans the
+/// changes with `dry_run`.
+pub fn handle_mv_command(
+    from: &str,
+    to: &str,
+    dry_run: bool,
+) -> Result<(NoteRename, Vec<FileChange>), Box<dyn std::error::Error>> {
+    let note_folder = PathBuf::from(command_folder_path(Sub::Note)?);
+    let from = PathBuf::from(resolve_note_path(from)?);
+    if !from.starts_with(&note_folder) {
+        return Err(
+            io::Error::other(format!("{} is not in the note folder", from.display())).into(),
+        );
+    }
+
+    let to = destination(&note_folder, to)?;
+    if to == from {
+        return Err(io::Error::other(format!("{} already has that name", from.display())).into());
+    }
+    if to.exists() {
+        return Err(io::Error::other(format!("{} already exists", to.display())).into());
+    }
+
+    let index = NoteIndex::open()?;
+    let name = to
+        .file_stem()
+        .map(|stem| normalized_name(&stem.to_string_lossy()))
+        .unwrap_or_default();
+    if let Some(existing) = index.notes.values().find(|note| {
+        Path::new(&note.path) != from
+            && Path::new(&note.path).starts_with(&note_folder)
+            && normalized_name(&note.name) == name
+    }) {
+        return Err(io::Error::other(format!(
+            "the new name would duplicate {}, links to it couldn't be told apart",
+            existing.path
+        ))
+        .into());
+    }
+
+    let rename = NoteRename { from, to };
+    let changes = plan_renames(&index, std::slice::from_ref(&rename))?;
+    if !dry_run {
+        apply_changes(&changes)?;
+    }
+
+    Ok((rename, changes))
+}
+
+/// The path of the note named `to`, relative to the note folder, with the configured case
+/// style applied to its file name.
+fn destination(note_folder: &Path, to: &str) -> io::Result<PathBuf> {
+    let to = to.trim_end_matches(".md");
+    let (folder, name) = to.rsplit_once('/').unwrap_or(("", to));
+
+    let folder = Path::new(folder);
+    if folder
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return Err(io::Error::other(format!(
+            "'{to}' must be a name or a path inside the note folder"
+        )));
+    }
+
+    Ok(note_folder
+        .join(folder)
+        .join(format!("{}.md", convert_case(name.to_owned()))))
+}
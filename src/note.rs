@@ -1,21 +1,71 @@
+use std::path::Path;
+
 use crate::{
-    config::Sub,
+    config::{Config, DuplicateNotes, Sub},
     utils::{
-        check_note_name, open_path_in_editor,
-        template::{check_template, insert_template_to_file},
+        capture::{append_entry, Append},
+        check_note_name,
+        clock::Clock,
+        date::Calendar,
+        open_path_in_editor,
+        placeholder::TemplateContext,
+        template::{
+            check_template, create_note_from_template, insert_template_to_file, NoteOptions,
+        },
     },
 };
 
 pub fn handle_note_command(
     template: &str,
     note_name: String,
+    options: &NoteOptions,
+    append: Option<&Append>,
 ) -> Result<String, Box<dyn std::error::Error>> {
     check_template(template)?;
 
-    if let Some(existing_path) = check_note_name(&note_name, Sub::Note)? {
-        open_path_in_editor(&existing_path)?;
-        return Ok(existing_path);
-    }
+    let existing_path = match check_note_name(&note_name, Sub::Note)? {
+        Some(path) if !is_named(&path, &note_name) => {
+            let open =
+                Config::read()?.general.duplicate_notes.unwrap_or_default() == DuplicateNotes::Open;
+            let action = if open { ", opening it" } else { "" };
+            eprintln!("warning: '{note_name}' matches the existing note {path}{action}");
+            open.then_some(path)
+        }
+        existing_path => existing_path,
+    };
+
+    let Some(append) = append else {
+        if let Some(existing_path) = existing_path {
+            open_path_in_editor(&existing_path)?;
+            return Ok(existing_path);
+        }
+
+        return insert_template_to_file(
+            template.to_owned(),
+            note_name.to_owned(),
+            Sub::Note,
+            options,
+        );
+    };
+
+    let path = match existing_path {
+        Some(existing_path) => existing_path,
+        None => {
+            create_note_from_template(template.to_owned(), note_name.clone(), Sub::Note, options)?
+        }
+    };
+
+    let clock = Clock::load()?;
+    let context = TemplateContext::new(note_name, clock.today())
+        .with_calendar(Calendar::load()?)
+        .with_now(clock.now);
+
+    append_entry(&path, append, context)?;
+    Ok(path)
+}
 
-    insert_template_to_file(template.to_owned(), note_name.to_owned(), Sub::Note)
+fn is_named(path: &str, name: &str) -> bool {
+    Path::new(path)
+        .file_stem()
+        .is_some_and(|stem| stem.to_string_lossy() == name)
 }
@@ -0,0 +1,64 @@
+use std::{
+    io,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::utils::{
+    clock::Clock,
+    date::Calendar,
+    fuzzy::fuzzy_score,
+    index::NoteIndex,
+    vault::{vault_notes, VaultNote},
+};
+
+/// Points added for a note edited just now, halved for every month since its last edit.
+const RECENCY_BONUS: f64 = 10.0;
+const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+
+#[derive(Debug, Serialize)]
+pub struct RankedNote {
+    #[serde(flatten)]
+    pub note: VaultNote,
+    pub title: String,
+    pub score: f64,
+}
+
+/// The notes and journal entries whose name or title fuzzy-match `query`, best match first.
+pub fn handle_open_command(query: &str) -> Result<Vec<RankedNote>, Box<dyn std::error::Error>> {
+    let index = NoteIndex::open()?;
+    let calendar = Calendar::load()?;
+    let today = Clock::load()?.today();
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(io::Error::other)?
+        .as_nanos() as u64;
+
+    let mut ranked: Vec<RankedNote> = vault_notes(&index, &calendar, today)?
+        .into_iter()
+        .filter_map(|note| {
+            let indexed = &index.notes[&note.path];
+            let match_score = [&indexed.name, &indexed.title]
+                .iter()
+                .filter_map(|candidate| fuzzy_score(query, candidate))
+                .max()?;
+
+            let days = now.saturating_sub(indexed.modified) as f64 / 86_400e9;
+            let recency = RECENCY_BONUS * 0.5_f64.powf(days / RECENCY_HALF_LIFE_DAYS);
+
+            Some(RankedNote {
+                title: indexed.title.clone(),
+                score: f64::from(match_score) + recency,
+                note,
+            })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(a.note.path.cmp(&b.note.path))
+    });
+    Ok(ranked)
+}
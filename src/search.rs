@@ -0,0 +1,154 @@
+use std::{fs, io};
+
+use chrono::{DateTime, Local, NaiveDate};
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+
+use crate::{
+    cli::SearchArgs,
+    config::Config,
+    utils::{
+        clock::Clock,
+        date::{parse_date_range, period_end, period_start, Calendar},
+        index::NoteIndex,
+        template::specific_template_info,
+        vault::{vault_notes, VaultNote},
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub note: VaultNote,
+    pub line: usize,
+    pub text: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// Every line matching the query in the notes and journal entries that pass the filters.
+pub fn handle_search_command(
+    args: &SearchArgs,
+) -> Result<Vec<SearchHit>, Box<dyn std::error::Error>> {
+    let pattern = search_pattern(args)?;
+    if let Some(journal) = &args.journal {
+        specific_template_info(journal)?;
+    }
+    let calendar = Calendar::load()?;
+    let today = Clock::load()?.today();
+
+    let range = args
+        .range
+        .as_deref()
+        .map(|range| parse_date_range(range, today, &calendar))
+        .transpose()
+        .map_err(io::Error::other)?;
+    let journals = Config::read()?.journal.unwrap_or_default();
+    let template_journals: Vec<&str> = match &args.template {
+        Some(template) => journals
+            .iter()
+            .filter(|journal| &journal.template == template)
+            .map(|journal| journal.name.as_str())
+            .collect(),
+        None => vec![],
+    };
+
+    let index = NoteIndex::open()?;
+    let mut hits = Vec::new();
+    for note in vault_notes(&index, &calendar, today)? {
+        if args.journal.is_some() && note.journal != args.journal {
+            continue;
+        }
+
+        if let Some(template) = &args.template {
+            let from_template = match &note.journal {
+                Some(journal) => template_journals.contains(&journal.as_str()),
+                None => index.notes[&note.path].front_matter.get("template") == Some(template),
+            };
+            if !from_template {
+                continue;
+            }
+        }
+
+        if let Some((start, end)) = range {
+            // A journal entry covers its whole period, which may straddle the range.
+            let period = note.date.and_then(|date| {
+                let journal = journals
+                    .iter()
+                    .find(|journal| note.journal.as_ref() == Some(&journal.name))?;
+                let calendar = calendar.for_format(&journal.format);
+                Some((
+                    period_start(date, journal.period(), &calendar),
+                    period_end(date, journal.period(), &calendar),
+                ))
+            });
+            let Some((first, last)) = period
+                .or_else(|| note.date.map(|date| (date, date)))
+                .or_else(|| modified_date(&note.path).map(|date| (date, date)))
+            else {
+                continue;
+            };
+            if last < start || first > end {
+                continue;
+            }
+        }
+
+        let Ok(contents) = fs::read_to_string(&note.path) else {
+            continue;
+        };
+
+        hits.extend(matching_lines(&note, &contents, &pattern, args.context));
+    }
+
+    Ok(hits)
+}
+
+/// A regex for the query, which is matched literally unless `--regex` is passed. Without a case
+/// flag the search is case-insensitive unless the query contains an uppercase letter.
+fn search_pattern(args: &SearchArgs) -> Result<Regex, Box<dyn std::error::Error>> {
+    let query = if args.regex {
+        args.query.clone()
+    } else {
+        regex::escape(&args.query)
+    };
+    let ignore_case = if args.ignore_case {
+        true
+    } else if args.case_sensitive {
+        false
+    } else {
+        !args.query.chars().any(char::is_uppercase)
+    };
+
+    RegexBuilder::new(&query)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|err| io::Error::other(format!("invalid search pattern: {err}")).into())
+}
+
+fn matching_lines(
+    note: &VaultNote,
+    contents: &str,
+    pattern: &Regex,
+    context: usize,
+) -> Vec<SearchHit> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let to_strings = |lines: &[&str]| lines.iter().map(|line| line.to_string()).collect();
+
+    lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| pattern.is_match(line))
+        .map(|(index, line)| SearchHit {
+            note: note.clone(),
+            line: index + 1,
+            text: line.to_string(),
+            before: to_strings(&lines[index.saturating_sub(context)..index]),
+            after: to_strings(&lines[index + 1..(index + 1 + context).min(lines.len())]),
+        })
+        .collect()
+}
+
+fn modified_date(path: &str) -> Option<NaiveDate> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(DateTime::<Local>::from(modified).date_naive())
+}
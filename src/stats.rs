@@ -0,0 +1,256 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+};
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+
+use crate::{
+    config::{Config, JournalPeriod},
+    utils::{
+        clock::Clock,
+        date::{parse_reference_date_input, period_end, period_start, Calendar},
+        index::{IndexedNote, NoteIndex},
+        markdown::front_matter_end,
+        vault::vault_notes,
+    },
+};
+
+#[derive(Debug, Serialize)]
+pub struct Stats {
+    pub since: Option<NaiveDate>,
+    pub notes: usize,
+    pub journal_entries: usize,
+    pub words: usize,
+    /// Notes and journal entries per template, from the front matter of notes and the
+    /// configuration of journals.
+    pub templates: BTreeMap<String, usize>,
+    pub without_template: usize,
+    pub journals: Vec<JournalStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JournalStats {
+    pub name: String,
+    pub period: JournalPeriod,
+    pub entries: usize,
+    pub words: usize,
+    /// Entries per `YYYY-MM`, by the date of their period.
+    pub entries_per_month: BTreeMap<String, usize>,
+    /// Consecutive periods with an entry up to the current one, or up to the previous one while
+    /// the current entry isn't written yet.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+}
+
+/// Counts the notes and journal entries dated on or after `since`: journal entries by the date
+/// of their period, other notes by their `created` front matter or else their last change.
+pub fn handle_stats_command(since: Option<&str>) -> Result<Stats, Box<dyn std::error::Error>> {
+    let calendar = Calendar::load()?;
+    let today = Clock::load()?.today();
+    let since = since
+        .map(|since| parse_reference_date_input(since, today, &calendar))
+        .transpose()
+        .map_err(io::Error::other)?;
+
+    let journals = Config::read()?.journal.unwrap_or_default();
+    let index = NoteIndex::open()?;
+
+    let mut stats = Stats {
+        since,
+        notes: 0,
+        journal_entries: 0,
+        words: 0,
+        templates: BTreeMap::new(),
+        without_template: 0,
+        journals: Vec::new(),
+    };
+    let mut journal_entries: BTreeMap<String, Vec<(NaiveDate, usize)>> = BTreeMap::new();
+
+    for note in vault_notes(&index, &calendar, today)? {
+        let indexed = &index.notes[&note.path];
+        let date = note.date.unwrap_or_else(|| note_date(indexed));
+        if since.is_some_and(|since| date < since) {
+            continue;
+        }
+
+        let words = fs::read_to_string(&note.path)
+            .map(|contents| word_count(&contents))
+            .unwrap_or(0);
+        stats.words += words;
+
+        let journal = note
+            .journal
+            .as_ref()
+            .and_then(|name| journals.iter().find(|journal| &journal.name == name));
+        let template = match journal {
+            Some(journal) => Some(&journal.template),
+            None => indexed.front_matter.get("template"),
+        };
+        match template {
+            Some(template) => *stats.templates.entry(template.clone()).or_default() += 1,
+            None => stats.without_template += 1,
+        }
+
+        match journal {
+            Some(journal) => {
+                stats.journal_entries += 1;
+                journal_entries
+                    .entry(journal.name.clone())
+                    .or_default()
+                    .push((date, words));
+            }
+            None => stats.notes += 1,
+        }
+    }
+
+    for journal in &journals {
+        let entries = journal_entries.remove(&journal.name).unwrap_or_default();
+        let period = journal.period();
+        let calendar = calendar.for_format(&journal.format);
+
+        let mut entries_per_month: BTreeMap<String, usize> = BTreeMap::new();
+        for (date, _) in &entries {
+            *entries_per_month
+                .entry(date.format("%Y-%m").to_string())
+                .or_default() += 1;
+        }
+
+        let starts: BTreeSet<NaiveDate> = entries
+            .iter()
+            .map(|(date, _)| period_start(*date, period, &calendar))
+            .collect();
+        let (current_streak, longest_streak) = streaks(&starts, today, period, &calendar);
+
+        stats.journals.push(JournalStats {
+            name: journal.name.clone(),
+            period,
+            entries: entries.len(),
+            words: entries.iter().map(|(_, words)| words).sum(),
+            entries_per_month,
+            current_streak,
+            longest_streak,
+        });
+    }
+
+    Ok(stats)
+}
+
+/// The current and the longest run of consecutive periods among `starts`, the start dates of
+/// the periods with an entry.
+fn streaks(
+    starts: &BTreeSet<NaiveDate>,
+    today: NaiveDate,
+    period: JournalPeriod,
+    calendar: &Calendar,
+) -> (usize, usize) {
+    let next = |start: NaiveDate| {
+        period_end(start, period, calendar)
+            .succ_opt()
+            .unwrap_or(start)
+    };
+    let previous = |start: NaiveDate| {
+        start
+            .pred_opt()
+            .map_or(start, |date| period_start(date, period, calendar))
+    };
+
+    let mut longest = 0;
+    let mut run = 0;
+    let mut last: Option<NaiveDate> = None;
+    for start in starts {
+        run = match last {
+            Some(last) if next(last) == *start => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        last = Some(*start);
+    }
+
+    let mut cursor = period_start(today, period, calendar);
+    if !starts.contains(&cursor) {
+        cursor = previous(cursor);
+    }
+    let mut current = 0;
+    while starts.contains(&cursor) {
+        current += 1;
+        cursor = previous(cursor);
+    }
+
+    (current, longest)
+}
+
+/// The `created` date of the front matter, or else the day the note last changed.
+fn note_date(note: &IndexedNote) -> NaiveDate {
+    note.front_matter
+        .get("created")
+        .and_then(|created| created.get(..10))
+        .and_then(|created| NaiveDate::parse_from_str(created, "%Y-%m-%d").ok())
+        .or_else(|| {
+            DateTime::from_timestamp_nanos(note.modified as i64)
+                .with_timezone(&Local)
+                .date_naive()
+                .into()
+        })
+        .unwrap_or_default()
+}
+
+/// The number of words outside the front matter.
+fn word_count(contents: &str) -> usize {
+    let lines: Vec<&str> = contents.lines().collect();
+
+    lines[front_matter_end(&lines)..]
+        .iter()
+        .map(|line| line.split_whitespace().count())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn counts_streaks_in_periods_of_the_journal() {
+        let calendar = Calendar::default();
+        let days: BTreeSet<NaiveDate> = [
+            date(2026, 4, 10),
+            date(2026, 4, 11),
+            date(2026, 4, 12),
+            date(2026, 4, 17),
+            date(2026, 4, 18),
+        ]
+        .into();
+
+        assert_eq!(
+            streaks(&days, date(2026, 4, 19), JournalPeriod::Day, &calendar),
+            (2, 3)
+        );
+        assert_eq!(
+            streaks(&days, date(2026, 4, 20), JournalPeriod::Day, &calendar),
+            (0, 3)
+        );
+
+        let weeks: BTreeSet<NaiveDate> = [date(2026, 3, 30), date(2026, 4, 6), date(2026, 4, 13)]
+            .into_iter()
+            .map(|date| period_start(date, JournalPeriod::Week, &calendar))
+            .collect();
+
+        assert_eq!(
+            streaks(&weeks, date(2026, 4, 22), JournalPeriod::Week, &calendar),
+            (3, 3)
+        );
+    }
+
+    #[test]
+    fn counts_words_outside_front_matter() {
+        assert_eq!(
+            word_count("---\ntitle: Notes\n---\n# Notes\nTwo  words\n"),
+            4
+        );
+    }
+}
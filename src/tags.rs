@@ -0,0 +1,60 @@
+use std::{cmp::Reverse, collections::BTreeMap};
+
+use serde::Serialize;
+
+use crate::utils::{
+    clock::Clock,
+    date::Calendar,
+    index::NoteIndex,
+    vault::{vault_notes, VaultNote},
+};
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    /// The number of notes carrying the tag.
+    pub count: usize,
+}
+
+/// Every tag of the note and journal folders, most used first. Tags that only differ in case
+/// are counted together under the first spelling found.
+pub fn handle_tags_command() -> Result<Vec<TagCount>, Box<dyn std::error::Error>> {
+    let index = NoteIndex::open()?;
+
+    let mut counts: BTreeMap<String, TagCount> = BTreeMap::new();
+    for note in index.notes.values() {
+        for tag in &note.tags {
+            counts
+                .entry(tag.to_lowercase())
+                .or_insert_with(|| TagCount {
+                    tag: tag.clone(),
+                    count: 0,
+                })
+                .count += 1;
+        }
+    }
+
+    let mut counts: Vec<TagCount> = counts.into_values().collect();
+    counts.sort_by_key(|count| Reverse(count.count));
+    Ok(counts)
+}
+
+/// The notes and journal entries tagged with `tag` or one of its nested tags, so `work` also
+/// matches `work/project`.
+pub fn handle_tag_notes_command(tag: &str) -> Result<Vec<VaultNote>, Box<dyn std::error::Error>> {
+    let index = NoteIndex::open()?;
+    let tag = tag.trim_start_matches('#').to_lowercase();
+    let nested = format!("{tag}/");
+
+    let notes = vault_notes(&index, &Calendar::load()?, Clock::load()?.today())?
+        .into_iter()
+        .filter(|note| {
+            index.notes[&note.path].tags.iter().any(|note_tag| {
+                let note_tag = note_tag.to_lowercase();
+                note_tag == tag || note_tag.starts_with(&nested)
+            })
+        })
+        .collect();
+
+    Ok(notes)
+}
@@ -0,0 +1,139 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    config::Sub,
+    utils::{
+        alternate_path, command_folder_path,
+        markdown::{append_block, insert_into_section},
+        placeholder::{Placeholder, TemplateContext},
+        write_atomically,
+    },
+};
+
+/// The bullet written by `--append` and, unless configured otherwise, by `zn capture`.
+pub const APPEND_TEMPLATE: &str = "- {{time}} {{text}}";
+
+/// The inbox used by `zn capture` when neither `inbox` nor `journal` is configured.
+pub const DEFAULT_INBOX: &str = "Inbox.md";
+
+/// Text to add to an existing note, optionally under a specific heading.
+pub struct Append {
+    pub text: String,
+    pub section: Option<String>,
+    pub template: String,
+}
+
+impl Append {
+    /// Reads the text from stdin when it is `-`.
+    pub fn new(text: &str, section: Option<&str>) -> io::Result<Self> {
+        Ok(Self {
+            text: read_text(text)?,
+            section: section.map(str::to_owned),
+            template: APPEND_TEMPLATE.to_owned(),
+        })
+    }
+
+    pub fn with_template(mut self, template: Option<String>) -> Self {
+        if let Some(template) = template {
+            self.template = template;
+        }
+        self
+    }
+
+    /// Keeps an explicitly requested section, otherwise falls back to `section`.
+    pub fn with_default_section(mut self, section: Option<String>) -> Self {
+        self.section = self.section.or(section);
+        self
+    }
+}
+
+pub fn read_text(input: &str) -> io::Result<String> {
+    let text = if input == "-" {
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        buffer
+    } else {
+        input.to_owned()
+    };
+
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(io::Error::other("nothing to append: the text is empty"));
+    }
+
+    Ok(text.to_owned())
+}
+
+/// Renders `template` with `{{text}}`, indenting continuation lines so they stay in the bullet.
+pub fn render_entry(
+    template: &str,
+    text: &str,
+    context: TemplateContext,
+) -> Result<String, String> {
+    let text = text.lines().collect::<Vec<_>>().join("\n  ");
+    Placeholder::parse(template.to_owned(), &context.with_text(text))
+}
+
+/// The inbox note, relative to the note folder unless it is an absolute or `~/` path.
+pub fn inbox_path(inbox: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+    let inbox = alternate_path(inbox.unwrap_or(DEFAULT_INBOX).to_owned());
+    let mut path = PathBuf::from(&inbox);
+
+    if path.is_relative() {
+        path = PathBuf::from(command_folder_path(Sub::Note)?).join(path);
+    }
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Appends the rendered entry to the note at `path`, under `append.section` when given.
+pub fn append_entry(
+    path: &str,
+    append: &Append,
+    context: TemplateContext,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let entry = render_entry(&append.template, &append.text, context).map_err(io::Error::other)?;
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            if let Some(parent) = Path::new(path).parent() {
+                fs::create_dir_all(parent)?;
+            }
+            String::new()
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    let updated = match &append.section {
+        Some(heading) => insert_into_section(&contents, heading, &entry),
+        None => append_block(&contents, &entry),
+    };
+
+    write_atomically(path, &updated)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, NaiveDate};
+
+    #[test]
+    fn renders_timestamped_bullet_with_indented_continuation() {
+        let now = DateTime::parse_from_rfc3339("2026-04-19T08:30:00Z").unwrap();
+        let context =
+            TemplateContext::new(String::new(), NaiveDate::from_ymd_opt(2026, 4, 19).unwrap())
+                .with_now(now);
+
+        let entry = render_entry(APPEND_TEMPLATE, "first line\nsecond line", context).unwrap();
+
+        assert_eq!(entry, "- 08:30 first line\n  second line");
+    }
+}
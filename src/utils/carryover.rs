@@ -0,0 +1,98 @@
+use std::{cell::RefCell, fs};
+
+use chrono::NaiveDate;
+
+use crate::{
+    config::JournalConfig,
+    utils::{
+        date::{period_start, Calendar},
+        markdown::{mark_tasks_migrated, unchecked_tasks},
+        template::journal_entries_on_disk,
+        write_atomically,
+    },
+};
+
+/// The most recent existing entry of `journal` from a period before the one of `reference_date`.
+pub fn previous_journal_entry(
+    journal: &JournalConfig,
+    reference_date: NaiveDate,
+    calendar: &Calendar,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let calendar = &calendar.for_format(&journal.format);
+    let current_start = period_start(reference_date, journal.period(), calendar);
+
+    Ok(journal_entries_on_disk(journal, calendar, reference_date)?
+        .into_iter()
+        .rev()
+        .find(|(date, _)| *date < current_start)
+        .map(|(_, path)| path))
+}
+
+/// The unchecked tasks a new journal entry takes from the previous one. Each section is only
+/// carried over once, however many times it is asked for, and nothing is marked as migrated in
+/// the previous entry until `finish` runs after the new entry is written.
+#[derive(Debug, Default)]
+pub struct CarryOver {
+    previous: Option<(String, String)>,
+    /// The sections taken so far, and whether their tasks get migrated.
+    taken: RefCell<Vec<(String, bool)>>,
+}
+
+impl CarryOver {
+    pub fn load(
+        journal: &JournalConfig,
+        reference_date: NaiveDate,
+        calendar: &Calendar,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let previous = match previous_journal_entry(journal, reference_date, calendar)? {
+            Some(path) => {
+                let contents = fs::read_to_string(&path)?;
+                Some((path, contents))
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            previous,
+            taken: RefCell::new(Vec::new()),
+        })
+    }
+
+    /// The unchecked tasks under `section` in the previous entry, or none when the section was
+    /// already carried over.
+    pub fn take(&self, section: &str, migrate: bool) -> Vec<String> {
+        let Some((_, contents)) = &self.previous else {
+            return vec![];
+        };
+
+        let mut taken = self.taken.borrow_mut();
+        if let Some((_, migrated)) = taken.iter_mut().find(|(taken, _)| taken == section) {
+            *migrated |= migrate;
+            return vec![];
+        }
+
+        taken.push((section.to_owned(), migrate));
+        unchecked_tasks(contents, section)
+    }
+
+    /// Marks the tasks taken with `migrate` as migrated in the previous entry.
+    pub fn finish(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some((path, contents)) = &self.previous else {
+            return Ok(());
+        };
+
+        let migrated = self
+            .taken
+            .borrow()
+            .iter()
+            .filter(|(_, migrate)| *migrate)
+            .fold(contents.clone(), |contents, (section, _)| {
+                mark_tasks_migrated(&contents, section)
+            });
+
+        if migrated != *contents {
+            write_atomically(path, &migrated)?;
+        }
+        Ok(())
+    }
+}
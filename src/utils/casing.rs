@@ -1,4 +1,5 @@
 use convert_case::{Case, Casing};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
 use crate::config::{CaseStyle, Config};
 
@@ -6,14 +7,70 @@ pub fn convert_case(note_title: String) -> String {
     let config = Config::read().unwrap();
 
     if let Some(case) = config.general.note_case_style {
-        match case {
-            CaseStyle::Camel => note_title.to_case(Case::Camel),
-            CaseStyle::Kebab => note_title.to_case(Case::Kebab),
-            CaseStyle::Pascal => note_title.to_case(Case::Pascal),
-            CaseStyle::Snake => note_title.to_case(Case::Snake),
-            CaseStyle::Original => note_title.to_string(),
-        }
+        convert_case_to(note_title, &case)
     } else {
         note_title
     }
 }
+
+pub fn convert_case_to(note_title: String, case: &CaseStyle) -> String {
+    match case {
+        CaseStyle::Camel => note_title.to_case(Case::Camel),
+        CaseStyle::Kebab => note_title.to_case(Case::Kebab),
+        CaseStyle::Pascal => note_title.to_case(Case::Pascal),
+        CaseStyle::Snake => note_title.to_case(Case::Snake),
+        CaseStyle::Original => note_title.to_string(),
+    }
+}
+
+/// The form note names are compared in to find duplicates. Every `CaseStyle` conversion of a
+/// title normalizes to the same value, and so do spellings that only differ in accents. Only
+/// case, accents and the separators the case styles use are folded, so `C++` and `C#` stay
+/// apart from `C`, and a name made of separators alone is kept as it is, in lowercase.
+pub fn normalized_name(name: &str) -> String {
+    let normalized: String = name
+        .nfkd()
+        .filter(|char| !is_combining_mark(*char) && !matches!(char, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    if normalized.is_empty() {
+        return name.to_lowercase();
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_every_case_style_and_accent_to_the_same_name() {
+        let title = "Café Meeting Notes";
+        let variants = [
+            title.to_case(Case::Camel),
+            title.to_case(Case::Kebab),
+            title.to_case(Case::Pascal),
+            title.to_case(Case::Snake),
+            String::from("cafe meeting notes"),
+        ];
+
+        for variant in variants {
+            assert_eq!(
+                normalized_name(&variant),
+                normalized_name(title),
+                "{variant}"
+            );
+        }
+        assert_eq!(normalized_name(title), "cafemeetingnotes");
+    }
+
+    #[test]
+    fn keeps_symbols_apart() {
+        assert_ne!(normalized_name("C++"), normalized_name("C"));
+        assert_ne!(normalized_name("C#"), normalized_name("C"));
+        assert_ne!(normalized_name("🚀"), normalized_name("🎉"));
+        assert_eq!(normalized_name("C++ Notes"), normalized_name("c++-notes"));
+        assert_eq!(normalized_name("--"), "--");
+    }
+}
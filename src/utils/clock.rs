@@ -0,0 +1,145 @@
+use std::env;
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::config::Config;
+
+/// The moment zapnote treats as "now", in the configured time zone.
+#[derive(Clone, Debug)]
+pub struct Clock {
+    pub now: DateTime<FixedOffset>,
+    pub day_starts_at: NaiveTime,
+}
+
+impl Clock {
+    /// Reads `--now` and `--tz` from the environment set by the CLI, falling back to the config.
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let config = Config::read()?;
+        let timezone = env::var("ZAPNOTE_TZ").ok().or(config.general.timezone);
+        let now = env::var("ZAPNOTE_NOW").ok();
+
+        let clock = Self::from_parts(now.as_deref(), timezone.as_deref())?
+            .with_day_start(config.general.day_starts_at.as_deref())?;
+
+        Ok(clock)
+    }
+
+    /// Overrides the time at which a new day begins, keeping the current one when `None`.
+    pub fn with_day_start(mut self, day_starts_at: Option<&str>) -> Result<Self, String> {
+        if let Some(day_starts_at) = day_starts_at {
+            self.day_starts_at = parse_day_start(day_starts_at)?;
+        }
+
+        Ok(self)
+    }
+
+    pub fn from_parts(now: Option<&str>, timezone: Option<&str>) -> Result<Self, String> {
+        let timezone = timezone.map(parse_timezone).transpose()?;
+
+        let now = match (now, timezone) {
+            (Some(input), _) => parse_now(input, timezone)?,
+            (None, Some(timezone)) => Utc::now().with_timezone(&timezone).fixed_offset(),
+            (None, None) => Local::now().fixed_offset(),
+        };
+
+        Ok(Self {
+            now,
+            day_starts_at: NaiveTime::MIN,
+        })
+    }
+
+    /// The current day, which only rolls over once `day_starts_at` has passed.
+    pub fn today(&self) -> NaiveDate {
+        let since_midnight = self.day_starts_at - NaiveTime::MIN;
+        (self.now.naive_local() - since_midnight).date()
+    }
+}
+
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>().map_err(|_| {
+        format!("unknown time zone '{name}'. use an IANA name such as UTC or Europe/Lisbon")
+    })
+}
+
+/// Parses a day start such as `04:00`, the time at which a new day begins.
+pub fn parse_day_start(input: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(input, "%H:%M")
+        .map_err(|_| format!("invalid day start '{input}'. use HH:MM, e.g. 04:00"))
+}
+
+fn parse_now(input: &str, timezone: Option<Tz>) -> Result<DateTime<FixedOffset>, String> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input) {
+        return Ok(match timezone {
+            Some(timezone) => datetime.with_timezone(&timezone).fixed_offset(),
+            None => datetime,
+        });
+    }
+
+    let naive = [
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+    .or_else(|| {
+        NaiveDate::parse_from_str(input, "%Y-%m-%d")
+            .ok()
+            .map(|date| date.and_time(NaiveTime::MIN))
+    })
+    .ok_or_else(|| {
+        format!(
+            "invalid --now '{input}'. use YYYY-MM-DDTHH:MM, YYYY-MM-DD or an RFC 3339 timestamp"
+        )
+    })?;
+
+    let localized = match timezone {
+        Some(timezone) => localize(&timezone, naive),
+        None => localize(&Local, naive),
+    };
+
+    localized.ok_or_else(|| format!("'{input}' does not exist in the configured time zone"))
+}
+
+fn localize<T: TimeZone>(timezone: &T, naive: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+    timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|datetime| datetime.fixed_offset())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_naive_now_in_configured_timezone() {
+        let clock = Clock::from_parts(Some("2026-04-19T08:30"), Some("America/Sao_Paulo")).unwrap();
+
+        assert_eq!(clock.now.to_rfc3339(), "2026-04-19T08:30:00-03:00");
+    }
+
+    #[test]
+    fn converts_rfc3339_now_into_configured_timezone() {
+        let clock = Clock::from_parts(Some("2026-04-19T23:30:00Z"), Some("Asia/Tokyo")).unwrap();
+
+        assert_eq!(clock.now.to_rfc3339(), "2026-04-20T08:30:00+09:00");
+    }
+
+    #[test]
+    fn today_rolls_over_at_day_start() {
+        let clock = Clock::from_parts(Some("2026-04-19T01:30"), Some("UTC"))
+            .unwrap()
+            .with_day_start(Some("04:00"))
+            .unwrap();
+
+        assert_eq!(clock.today(), NaiveDate::from_ymd_opt(2026, 4, 18).unwrap());
+    }
+
+    #[test]
+    fn rejects_unknown_timezones() {
+        assert!(Clock::from_parts(None, Some("Mars/Olympus")).is_err());
+    }
+}
@@ -1,13 +1,23 @@
-use chrono::{DateTime, Datelike, Days, Local, Months, NaiveDate, Weekday};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write,
+};
+
+use chrono::{DateTime, Datelike, Days, Locale, Months, NaiveDate, TimeZone, Weekday};
 use regex::Regex;
 
-use crate::utils::quarter_from_week;
+use crate::{
+    config::{Config, GeneralConfig, JournalPeriod, QuarterStyle, WeekStart},
+    utils::{alternate_path, holidays::load_holidays, quarter_from_week},
+};
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum DateOffsetUnit {
     Day,
+    Weekday,
     Week,
     Quarter,
+    HalfYear,
     Month,
     Year,
 }
@@ -18,82 +28,655 @@ pub struct DateOffset {
     unit: DateOffsetUnit,
 }
 
-pub fn format_date(date: NaiveDate, format: &str) -> String {
-    let processed = replace_quarter(format, date);
-    date.format(&processed).to_string()
+/// Calendar conventions used to resolve weeks, quarters and fiscal years.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Calendar {
+    pub week_start: Weekday,
+    /// `None` keeps the historical behaviour: `%Q` counts ISO weeks while quarter periods,
+    /// `2026-Q1` dates and quarter offsets count months.
+    pub quarter_style: Option<QuarterStyle>,
+    pub fiscal_year_start_month: u32,
+    /// Whether quarter and year periods follow the fiscal year, see `Calendar::for_format`.
+    pub fiscal_periods: bool,
+    pub locale: Option<Locale>,
+    pub holidays: BTreeSet<NaiveDate>,
 }
 
-pub fn format_datetime(datetime: DateTime<Local>, format: &str) -> String {
-    let processed = replace_quarter(format, datetime.date_naive());
-    datetime.format(&processed).to_string()
+impl Default for Calendar {
+    fn default() -> Self {
+        Self {
+            week_start: Weekday::Mon,
+            quarter_style: None,
+            fiscal_year_start_month: 1,
+            fiscal_periods: false,
+            locale: None,
+            holidays: BTreeSet::new(),
+        }
+    }
 }
 
-pub fn parse_date_offset(input: &str) -> Result<DateOffset, String> {
-    let regex = Regex::new(
-        r#"^\s*([+-]?\d+)\s*(day|days|week|weeks|quarter|quarters|month|months|year|years)\s*$"#,
-    )
-    .unwrap();
-    let captures = regex
-        .captures(input)
-        .ok_or_else(|| format!("invalid offset '{input}'"))?;
+/// A format token zapnote expands itself before handing the rest of the format to chrono.
+pub struct DateToken {
+    pub token: &'static str,
+    /// The most specific period the token depends on, used to infer journal periods.
+    pub period: JournalPeriod,
+    render: fn(NaiveDate, &Calendar) -> String,
+}
 
-    let amount = captures[1]
-        .parse::<i32>()
-        .map_err(|_| format!("invalid offset amount in '{input}'"))?;
-
-    let unit = match &captures[2] {
-        "day" | "days" => DateOffsetUnit::Day,
-        "week" | "weeks" => DateOffsetUnit::Week,
-        "quarter" | "quarters" => DateOffsetUnit::Quarter,
-        "month" | "months" => DateOffsetUnit::Month,
-        "year" | "years" => DateOffsetUnit::Year,
-        _ => unreachable!(),
-    };
+/// Custom tokens, checked in order, so longer tokens must come before their prefixes.
+pub const DATE_TOKENS: &[DateToken] = &[
+    DateToken {
+        token: "%FY",
+        period: JournalPeriod::Year,
+        render: |date, calendar| calendar.fiscal_year(date).to_string(),
+    },
+    DateToken {
+        token: "%Fy",
+        period: JournalPeriod::Year,
+        render: |date, calendar| format!("{:02}", calendar.fiscal_year(date).rem_euclid(100)),
+    },
+    DateToken {
+        token: "%FQ",
+        period: JournalPeriod::Quarter,
+        render: |date, calendar| calendar.fiscal_quarter(date).to_string(),
+    },
+    DateToken {
+        token: "%FH",
+        period: JournalPeriod::HalfYear,
+        render: |date, calendar| (calendar.months_into_fiscal_year(date) / 6 + 1).to_string(),
+    },
+    DateToken {
+        token: "%Q",
+        period: JournalPeriod::Quarter,
+        render: |date, calendar| calendar.quarter(date).to_string(),
+    },
+    DateToken {
+        token: "%EH",
+        period: JournalPeriod::HalfYear,
+        render: |date, _| ((date.month() - 1) / 6 + 1).to_string(),
+    },
+    DateToken {
+        token: "%EW",
+        period: JournalPeriod::Week,
+        render: |date, calendar| {
+            let first = date.with_day(1).unwrap();
+            let leading_days = first.weekday().days_since(calendar.week_start);
+            ((date.day() + leading_days - 1) / 7 + 1).to_string()
+        },
+    },
+    DateToken {
+        token: "%EQ",
+        period: JournalPeriod::Day,
+        render: |date, calendar| {
+            let start = period_start(date, JournalPeriod::Quarter, calendar);
+            ((date - start).num_days() + 1).to_string()
+        },
+    },
+    DateToken {
+        token: "%Eb",
+        period: JournalPeriod::Month,
+        render: |date, calendar| localized(date, "%b", calendar),
+    },
+    DateToken {
+        token: "%EB",
+        period: JournalPeriod::Month,
+        render: |date, calendar| localized(date, "%B", calendar),
+    },
+    DateToken {
+        token: "%Ea",
+        period: JournalPeriod::Day,
+        render: |date, calendar| localized(date, "%a", calendar),
+    },
+    DateToken {
+        token: "%EA",
+        period: JournalPeriod::Day,
+        render: |date, calendar| localized(date, "%A", calendar),
+    },
+    DateToken {
+        token: "%o",
+        period: JournalPeriod::Day,
+        render: |date, _| ordinal(date.day()),
+    },
+];
+
+enum FormatToken<'a> {
+    Literal(&'a str),
+    Custom(&'static DateToken),
+    Chrono(&'a str),
+}
+
+impl Calendar {
+    pub fn from_config(general: &GeneralConfig) -> Result<Self, String> {
+        let fiscal_year_start_month = general.fiscal_year_start_month.unwrap_or(1);
+
+        if !(1..=12).contains(&fiscal_year_start_month) {
+            return Err(format!(
+                "invalid fiscal_year_start_month '{fiscal_year_start_month}'. use a month between 1 and 12"
+            ));
+        }
+
+        Ok(Self {
+            week_start: match general.week_start {
+                Some(WeekStart::Sunday) => Weekday::Sun,
+                Some(WeekStart::Monday) | None => Weekday::Mon,
+            },
+            quarter_style: general.quarter_style,
+            fiscal_year_start_month,
+            fiscal_periods: false,
+            locale: general
+                .locale
+                .as_deref()
+                .map(|name| {
+                    Locale::try_from(name).map_err(|_| format!("unsupported locale '{name}'"))
+                })
+                .transpose()?,
+            holidays: match &general.holidays_path {
+                Some(path) => load_holidays(&alternate_path(path.clone()))?,
+                None => BTreeSet::new(),
+            },
+        })
+    }
+
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let config = Config::read()?;
+        Ok(Self::from_config(&config.general)?)
+    }
+
+    /// The calendar for the periods of a journal named by `format`. Quarters and years only
+    /// follow the fiscal year when the format has a fiscal token such as `%FY`, and then count
+    /// months even with `quarter_style = "iso-week"`.
+    pub fn for_format(&self, format: &str) -> Self {
+        let fiscal = tokenize_format(format).unwrap_or_default().iter().any(
+            |token| matches!(token, FormatToken::Custom(custom) if custom.token.starts_with("%F")),
+        );
+
+        Self {
+            fiscal_periods: fiscal,
+            ..self.clone()
+        }
+    }
+
+    /// The quarter used by `%Q`, either by calendar months or by ISO week, which is the default.
+    pub fn quarter<T: Datelike>(&self, date: T) -> u32 {
+        match self.quarter_style.unwrap_or(QuarterStyle::IsoWeek) {
+            QuarterStyle::Calendar => (date.month() - 1) / 3 + 1,
+            QuarterStyle::IsoWeek => quarter_from_week(date.iso_week().week()),
+        }
+    }
+
+    /// The fiscal year, named after the calendar year in which it ends.
+    pub fn fiscal_year<T: Datelike>(&self, date: T) -> i32 {
+        if self.fiscal_year_start_month > 1 && date.month() >= self.fiscal_year_start_month {
+            date.year() + 1
+        } else {
+            date.year()
+        }
+    }
+
+    pub fn fiscal_quarter<T: Datelike>(&self, date: T) -> u32 {
+        self.months_into_fiscal_year(date) / 3 + 1
+    }
+
+    /// Whether `date` is a weekday that is not listed as a holiday.
+    pub fn is_business_day(&self, date: NaiveDate) -> bool {
+        !matches!(date.weekday(), Weekday::Sat | Weekday::Sun) && !self.holidays.contains(&date)
+    }
+
+    /// Whether quarter periods are made of ISO weeks rather than months.
+    fn quarters_by_week(&self) -> bool {
+        self.quarter_style == Some(QuarterStyle::IsoWeek) && !self.fiscal_periods
+    }
+
+    /// Months since the start of the year the periods follow, fiscal or calendar.
+    fn months_into_period_year<T: Datelike>(&self, date: T) -> u32 {
+        if self.fiscal_periods {
+            self.months_into_fiscal_year(date)
+        } else {
+            date.month() - 1
+        }
+    }
+
+    fn months_into_fiscal_year<T: Datelike>(&self, date: T) -> u32 {
+        (date.month() + 12 - self.fiscal_year_start_month) % 12
+    }
+
+    fn fiscal_year_start(&self, fiscal_year: i32) -> Option<NaiveDate> {
+        let year = if self.fiscal_year_start_month > 1 {
+            fiscal_year - 1
+        } else {
+            fiscal_year
+        };
+
+        NaiveDate::from_ymd_opt(year, self.fiscal_year_start_month, 1)
+    }
+}
+
+impl From<JournalPeriod> for DateOffsetUnit {
+    fn from(period: JournalPeriod) -> Self {
+        match period {
+            JournalPeriod::Day => DateOffsetUnit::Day,
+            JournalPeriod::Week => DateOffsetUnit::Week,
+            JournalPeriod::Month => DateOffsetUnit::Month,
+            JournalPeriod::Quarter => DateOffsetUnit::Quarter,
+            JournalPeriod::HalfYear => DateOffsetUnit::HalfYear,
+            JournalPeriod::Year => DateOffsetUnit::Year,
+        }
+    }
+}
+
+pub fn format_date(date: NaiveDate, format: &str, calendar: &Calendar) -> Result<String, String> {
+    let processed = expand_custom_tokens(format, date, calendar)?;
+    let mut formatted = String::new();
 
-    Ok(DateOffset { amount, unit })
+    write!(formatted, "{}", date.format(&processed))
+        .map_err(|_| format!("date format '{format}' needs a time, which is not available here"))?;
+
+    Ok(formatted)
+}
+
+pub fn format_datetime<Tz: TimeZone>(
+    datetime: &DateTime<Tz>,
+    format: &str,
+    calendar: &Calendar,
+) -> Result<String, String>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let processed = expand_custom_tokens(format, datetime.date_naive(), calendar)?;
+    let mut formatted = String::new();
+
+    write!(formatted, "{}", datetime.format(&processed))
+        .map_err(|_| format!("invalid time format '{format}'"))?;
+
+    Ok(formatted)
 }
 
-pub fn apply_date_offset(date: NaiveDate, offset: DateOffset) -> NaiveDate {
+/// Examples of relative date expressions, shared by error messages.
+const RELATIVE_DATE_SHAPES: &str = "today, yesterday, tomorrow, last friday, next monday, +1 day, +1 weekday, +1 week -2 days, 3 days ago, in 2 weeks, or end of month";
+
+/// Parses one or more signed offsets such as `+1 week -2 days`, `3 days ago` or `in 2 weeks`.
+pub fn parse_date_offsets(input: &str) -> Result<Vec<DateOffset>, String> {
+    let normalized = input.trim().to_lowercase();
+
+    if let Some(rest) = normalized.strip_prefix("in ") {
+        return parse_date_offsets(rest);
+    }
+
+    if let Some(rest) = normalized.strip_suffix(" ago") {
+        return Ok(parse_date_offsets(rest)?
+            .into_iter()
+            .map(|offset| DateOffset {
+                amount: -offset.amount,
+                unit: offset.unit,
+            })
+            .collect());
+    }
+
+    let regex = Regex::new(r"([+-]?\d+)\s*(business days?|[a-z]+)").unwrap();
+    let mut offsets = Vec::new();
+    let mut last_end = 0;
+
+    for captures in regex.captures_iter(&normalized) {
+        let whole = captures.get(0).unwrap();
+        if !normalized[last_end..whole.start()].trim().is_empty() {
+            return Err(format!("invalid offset '{input}'"));
+        }
+
+        let amount = captures[1]
+            .parse::<i32>()
+            .map_err(|_| format!("invalid offset amount in '{input}'"))?;
+        let unit = parse_offset_unit(&captures[2])
+            .ok_or_else(|| format!("invalid offset unit '{}' in '{input}'", &captures[2]))?;
+
+        offsets.push(DateOffset { amount, unit });
+        last_end = whole.end();
+    }
+
+    if offsets.is_empty() || !normalized[last_end..].trim().is_empty() {
+        return Err(format!("invalid offset '{input}'"));
+    }
+
+    Ok(offsets)
+}
+
+/// Resolves a natural-language expression or a list of offsets against `base`.
+pub fn resolve_relative_date(
+    base: NaiveDate,
+    input: &str,
+    calendar: &Calendar,
+) -> Result<NaiveDate, String> {
+    let normalized = input.trim().to_lowercase();
+
+    match normalized.as_str() {
+        "today" => return Ok(base),
+        "yesterday" => return Ok(add_days(base, -1)),
+        "tomorrow" => return Ok(add_days(base, 1)),
+        _ => {}
+    }
+
+    if let Some((direction, rest)) = normalized.split_once(' ') {
+        let step = match direction {
+            "last" => Some(-1),
+            "next" => Some(1),
+            _ => None,
+        };
+
+        if let Some(step) = step {
+            if let Ok(weekday) = rest.parse::<Weekday>() {
+                let mut date = add_days(base, step);
+                while date.weekday() != weekday {
+                    date = add_days(date, step);
+                }
+                return Ok(date);
+            }
+
+            if let Some(unit) = parse_offset_unit(rest) {
+                let offset = DateOffset { amount: step, unit };
+                return Ok(apply_date_offset(base, offset, calendar));
+            }
+        }
+    }
+
+    let boundary =
+        Regex::new(r"^(start|beginning|end) of (?:the )?(week|month|quarter|year)$").unwrap();
+    if let Some(captures) = boundary.captures(&normalized) {
+        let period = match &captures[2] {
+            "week" => JournalPeriod::Week,
+            "month" => JournalPeriod::Month,
+            "quarter" => JournalPeriod::Quarter,
+            _ => JournalPeriod::Year,
+        };
+
+        return Ok(match &captures[1] {
+            "end" => period_end(base, period, calendar),
+            _ => period_start(base, period, calendar),
+        });
+    }
+
+    let offsets = parse_date_offsets(&normalized)
+        .map_err(|_| format!("invalid date expression '{input}'. use {RELATIVE_DATE_SHAPES}"))?;
+
+    Ok(offsets.into_iter().fold(base, |date, offset| {
+        apply_date_offset(date, offset, calendar)
+    }))
+}
+
+pub fn apply_date_offset(date: NaiveDate, offset: DateOffset, calendar: &Calendar) -> NaiveDate {
     match offset.unit {
         DateOffsetUnit::Day => add_days(date, offset.amount),
+        DateOffsetUnit::Weekday => add_business_days(date, offset.amount, calendar),
         DateOffsetUnit::Week => add_days(date, offset.amount.saturating_mul(7)),
+        DateOffsetUnit::Quarter if calendar.quarters_by_week() => {
+            add_iso_quarters(date, offset.amount)
+        }
         DateOffsetUnit::Quarter => add_months(date, offset.amount.saturating_mul(3)),
         DateOffsetUnit::Month => add_months(date, offset.amount),
+        DateOffsetUnit::HalfYear => add_months(date, offset.amount.saturating_mul(6)),
         DateOffsetUnit::Year => add_months(date, offset.amount.saturating_mul(12)),
     }
 }
 
+/// Resolves the date a journal entry is anchored to, snapped to the start of its period.
+///
+/// A bare offset amount such as `+1` moves by whole periods of the journal.
 pub fn resolve_reference_date(
     date_input: Option<&str>,
     offset_input: Option<&str>,
+    period: JournalPeriod,
+    calendar: &Calendar,
+    today: NaiveDate,
 ) -> Result<NaiveDate, String> {
     let base_date = match date_input {
-        Some(input) => parse_reference_date_input(input)?,
-        None => Local::now().date_naive(),
+        Some(input) => parse_reference_date_input(input, today, calendar)?,
+        None => today,
     };
+    let base_date = period_start(base_date, period, calendar);
 
     match offset_input {
         Some(input) => {
-            let offset = parse_date_offset(input)?;
-            Ok(apply_date_offset(base_date, offset))
+            let shifted = match input.trim().parse::<i32>() {
+                Ok(amount) => {
+                    let offset = DateOffset {
+                        amount,
+                        unit: period.into(),
+                    };
+                    apply_date_offset(base_date, offset, calendar)
+                }
+                Err(_) => resolve_relative_date(base_date, input, calendar).map_err(|_| {
+                    format!("invalid offset '{input}'. use +1 for the next period, {RELATIVE_DATE_SHAPES}")
+                })?,
+            };
+
+            Ok(period_start(shifted, period, calendar))
         }
         None => Ok(base_date),
     }
 }
 
-pub fn parse_reference_date_input(input: &str) -> Result<NaiveDate, String> {
+/// Quarters and years follow the fiscal year only for calendars of fiscal formats, see
+/// `Calendar::for_format`.
+pub fn period_start(date: NaiveDate, period: JournalPeriod, calendar: &Calendar) -> NaiveDate {
+    match period {
+        JournalPeriod::Day => date,
+        JournalPeriod::Week => {
+            let days_into_week = date.weekday().days_since(calendar.week_start);
+            add_days(date, -(days_into_week as i32))
+        }
+        JournalPeriod::Month => date.with_day(1).unwrap(),
+        JournalPeriod::Quarter if calendar.quarters_by_week() => {
+            let quarter = calendar.quarter(date);
+            iso_quarter_start(date.iso_week().year(), quarter)
+        }
+        JournalPeriod::Quarter => {
+            let months_into_quarter = calendar.months_into_period_year(date) % 3;
+            add_months(date.with_day(1).unwrap(), -(months_into_quarter as i32))
+        }
+        JournalPeriod::HalfYear => {
+            let months_into_half = calendar.months_into_period_year(date) % 6;
+            add_months(date.with_day(1).unwrap(), -(months_into_half as i32))
+        }
+        JournalPeriod::Year => {
+            let months_into_year = calendar.months_into_period_year(date);
+            add_months(date.with_day(1).unwrap(), -(months_into_year as i32))
+        }
+    }
+}
+
+pub fn period_end(date: NaiveDate, period: JournalPeriod, calendar: &Calendar) -> NaiveDate {
+    let start = period_start(date, period, calendar);
+
+    if period == JournalPeriod::Quarter && calendar.quarters_by_week() {
+        return iso_quarter_end(start.iso_week().year(), calendar.quarter(start));
+    }
+
+    let next_start = apply_date_offset(
+        start,
+        DateOffset {
+            amount: 1,
+            unit: period.into(),
+        },
+        calendar,
+    );
+
+    add_days(next_start, -1)
+}
+
+/// Guesses the period of a journal from the most specific field in its filename format.
+pub fn infer_period(format: &str) -> JournalPeriod {
+    let mut periods = Vec::new();
+
+    for token in tokenize_format(format).unwrap_or_default() {
+        match token {
+            FormatToken::Custom(custom) => periods.push(custom.period),
+            FormatToken::Chrono(spec) => {
+                let specifier = spec.trim_start_matches(['%', '-', '_', '0', '#']);
+                if let Some(period) = chrono_specifier_period(specifier) {
+                    periods.push(period);
+                }
+            }
+            FormatToken::Literal(_) => {}
+        }
+    }
+
+    [
+        JournalPeriod::Day,
+        JournalPeriod::Week,
+        JournalPeriod::Month,
+        JournalPeriod::Quarter,
+        JournalPeriod::HalfYear,
+        JournalPeriod::Year,
+    ]
+    .into_iter()
+    .find(|period| periods.contains(period))
+    .unwrap_or(JournalPeriod::Day)
+}
+
+/// Parses an absolute anchor such as `2026-W16`, or an expression relative to `today`.
+pub fn parse_reference_date_input(
+    input: &str,
+    today: NaiveDate,
+    calendar: &Calendar,
+) -> Result<NaiveDate, String> {
     NaiveDate::parse_from_str(input, "%Y-%m-%d")
         .or_else(|_| parse_iso_week_date(input))
-        .or_else(|_| parse_quarter_date(input))
+        .or_else(|_| parse_quarter_date(input, calendar))
         .or_else(|_| parse_month_date(input))
         .or_else(|_| parse_year_date(input))
+        .or_else(|_| parse_fiscal_year_date(input, calendar))
+        .or_else(|_| resolve_relative_date(today, input, calendar))
         .map_err(|_| {
             format!(
-                "unsupported --date '{input}'. use YYYY-MM-DD, YYYY-W01, YYYY-Q1, YYYY-MM, or YYYY"
+                "unsupported --date '{input}'. use YYYY-MM-DD, YYYY-W01, YYYY-Q1, FYYYYY-Q1, YYYY-MM, YYYY, FYYYYY, or {RELATIVE_DATE_SHAPES}"
             )
         })
 }
 
+/// Parses `start..end` or a single anchor such as `2026-W17` into an inclusive range of days.
+///
+/// Each bound covers its whole anchor, so `2026-W17..2026-W18` ends on the Sunday of week 18.
+pub fn parse_date_range(
+    input: &str,
+    today: NaiveDate,
+    calendar: &Calendar,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    let (start, end) = match input.split_once("..") {
+        Some((start, end)) => (
+            parse_reference_span(start.trim(), today, calendar)?.0,
+            parse_reference_span(end.trim(), today, calendar)?.1,
+        ),
+        None => parse_reference_span(input.trim(), today, calendar)?,
+    };
+
+    if start > end {
+        return Err(format!(
+            "invalid --range '{input}'. the start is after the end"
+        ));
+    }
+
+    Ok((start, end))
+}
+
+/// The start of every period that overlaps the inclusive range between `start` and `end`.
+pub fn period_starts_between(
+    start: NaiveDate,
+    end: NaiveDate,
+    period: JournalPeriod,
+    calendar: &Calendar,
+) -> Vec<NaiveDate> {
+    let mut dates = Vec::new();
+    let mut cursor = period_start(start, period, calendar);
+
+    while cursor <= end {
+        dates.push(cursor);
+        cursor = add_days(period_end(cursor, period, calendar), 1);
+    }
+
+    dates
+}
+
+/// Recovers the period behind each name produced by `format`, by formatting every period of
+/// the years the names mention and matching the results. Names that don't match are dropped.
+pub fn parse_formatted_dates<'a>(
+    names: &[&'a str],
+    format: &str,
+    period: JournalPeriod,
+    calendar: &Calendar,
+    today: NaiveDate,
+) -> Result<Vec<(&'a str, NaiveDate)>, String> {
+    let year_regex = Regex::new(r"\d{4}").unwrap();
+    let mut years: BTreeSet<i32> = names
+        .iter()
+        .flat_map(|name| year_regex.find_iter(name))
+        .filter_map(|year| year.as_str().parse::<i32>().ok())
+        .flat_map(|year| [year - 1, year, year + 1])
+        .collect();
+
+    if years.is_empty() {
+        years.extend([today.year() - 1, today.year(), today.year() + 1]);
+    }
+
+    let mut formatted: HashMap<String, NaiveDate> = HashMap::new();
+    for year in years {
+        let (Some(start), Some(end)) = (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year, 12, 31),
+        ) else {
+            continue;
+        };
+
+        for date in period_starts_between(start, end, period, calendar) {
+            formatted
+                .entry(format_date(date, format, calendar)?)
+                .or_insert(date);
+        }
+    }
+
+    Ok(names
+        .iter()
+        .filter_map(|name| formatted.get(*name).map(|date| (*name, *date)))
+        .collect())
+}
+
+fn parse_reference_span(
+    input: &str,
+    today: NaiveDate,
+    calendar: &Calendar,
+) -> Result<(NaiveDate, NaiveDate), String> {
+    let start = parse_reference_date_input(input, today, calendar)?;
+
+    let end = if parse_iso_week_date(input).is_ok() {
+        add_days(start, 6)
+    } else if parse_quarter_date(input, calendar).is_ok() {
+        if !input.starts_with("FY") && calendar.quarter_style == Some(QuarterStyle::IsoWeek) {
+            iso_quarter_end(start.iso_week().year(), calendar.quarter(start))
+        } else {
+            add_days(add_months(start, 3), -1)
+        }
+    } else if parse_month_date(input).is_ok() {
+        add_days(add_months(start, 1), -1)
+    } else if parse_year_date(input).is_ok() || parse_fiscal_year_date(input, calendar).is_ok() {
+        add_days(add_months(start, 12), -1)
+    } else {
+        start
+    };
+
+    Ok((start, end))
+}
+
+fn parse_offset_unit(unit: &str) -> Option<DateOffsetUnit> {
+    match unit {
+        "day" | "days" => Some(DateOffsetUnit::Day),
+        "weekday" | "weekdays" | "workday" | "workdays" | "business day" | "business days" => {
+            Some(DateOffsetUnit::Weekday)
+        }
+        "week" | "weeks" => Some(DateOffsetUnit::Week),
+        "quarter" | "quarters" => Some(DateOffsetUnit::Quarter),
+        "month" | "months" => Some(DateOffsetUnit::Month),
+        "year" | "years" => Some(DateOffsetUnit::Year),
+        _ => None,
+    }
+}
+
 fn add_days(date: NaiveDate, amount: i32) -> NaiveDate {
     if amount >= 0 {
         date.checked_add_days(Days::new(amount as u64)).unwrap()
@@ -103,6 +686,20 @@ fn add_days(date: NaiveDate, amount: i32) -> NaiveDate {
     }
 }
 
+fn add_business_days(date: NaiveDate, amount: i32, calendar: &Calendar) -> NaiveDate {
+    let step = amount.signum();
+    let mut shifted = date;
+
+    for _ in 0..amount.unsigned_abs() {
+        shifted = add_days(shifted, step);
+        while !calendar.is_business_day(shifted) {
+            shifted = add_days(shifted, step);
+        }
+    }
+
+    shifted
+}
+
 fn add_months(date: NaiveDate, amount: i32) -> NaiveDate {
     if amount >= 0 {
         date.checked_add_months(Months::new(amount as u32)).unwrap()
@@ -112,9 +709,120 @@ fn add_months(date: NaiveDate, amount: i32) -> NaiveDate {
     }
 }
 
-fn replace_quarter<T: Datelike>(format: &str, date: T) -> String {
+fn chrono_specifier_period(specifier: &str) -> Option<JournalPeriod> {
+    match specifier {
+        "d" | "e" | "j" | "a" | "A" | "u" | "w" | "F" | "D" | "x" | "c" | "v" => {
+            Some(JournalPeriod::Day)
+        }
+        "V" | "W" | "U" => Some(JournalPeriod::Week),
+        "m" | "b" | "B" | "h" => Some(JournalPeriod::Month),
+        "Y" | "G" | "y" | "g" | "C" => Some(JournalPeriod::Year),
+        _ => None,
+    }
+}
+
+/// Splits a format into literals, zapnote tokens and chrono specifiers, rejecting anything else.
+fn tokenize_format(format: &str) -> Result<Vec<FormatToken<'_>>, String> {
+    let chrono_specifier = Regex::new(
+        r"^%(?:[-_0]?[aAbBcCdDeFgGhHIjklmMnpPrRsStTuUvVwWxXyYzZ%+]|\.?[369]?f|:{1,3}z|#z)",
+    )
+    .unwrap();
+    let mut tokens = Vec::new();
+    let mut rest = format;
+
+    while let Some(index) = rest.find('%') {
+        if index > 0 {
+            tokens.push(FormatToken::Literal(&rest[..index]));
+        }
+        rest = &rest[index..];
+
+        if let Some(custom) = DATE_TOKENS
+            .iter()
+            .find(|custom| rest.starts_with(custom.token))
+        {
+            tokens.push(FormatToken::Custom(custom));
+            rest = &rest[custom.token.len()..];
+        } else if let Some(spec) = chrono_specifier.find(rest) {
+            tokens.push(FormatToken::Chrono(spec.as_str()));
+            rest = &rest[spec.end()..];
+        } else {
+            let unknown: String = rest.chars().take(2).collect();
+            return Err(format!(
+                "unknown date format token '{unknown}' in '{format}'"
+            ));
+        }
+    }
+
+    if !rest.is_empty() {
+        tokens.push(FormatToken::Literal(rest));
+    }
+
+    Ok(tokens)
+}
+
+fn expand_custom_tokens(
+    format: &str,
+    date: NaiveDate,
+    calendar: &Calendar,
+) -> Result<String, String> {
+    let expanded = tokenize_format(format)?
+        .into_iter()
+        .map(|token| match token {
+            FormatToken::Literal(literal) | FormatToken::Chrono(literal) => literal.to_string(),
+            FormatToken::Custom(custom) => (custom.render)(date, calendar).replace('%', "%%"),
+        })
+        .collect();
+
+    Ok(expanded)
+}
+
+fn localized(date: NaiveDate, format: &str, calendar: &Calendar) -> String {
+    match calendar.locale {
+        Some(locale) => date.format_localized(format, locale).to_string(),
+        None => date.format(format).to_string(),
+    }
+}
+
+fn ordinal(day: u32) -> String {
+    let suffix = match (day % 10, day % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{day}{suffix}")
+}
+
+fn add_iso_quarters(date: NaiveDate, amount: i32) -> NaiveDate {
+    let year = date.iso_week().year();
     let quarter = quarter_from_week(date.iso_week().week());
-    format.replace("%Q", &quarter.to_string())
+    let days_into_quarter = (date - iso_quarter_start(year, quarter)).num_days() as i32;
+
+    let target = year * 4 + quarter as i32 - 1 + amount;
+    let target_year = target.div_euclid(4);
+    let target_quarter = target.rem_euclid(4) as u32 + 1;
+
+    let shifted = add_days(
+        iso_quarter_start(target_year, target_quarter),
+        days_into_quarter,
+    );
+    shifted.min(iso_quarter_end(target_year, target_quarter))
+}
+
+fn iso_quarter_start(year: i32, quarter: u32) -> NaiveDate {
+    NaiveDate::from_isoywd_opt(year, (quarter - 1) * 13 + 1, Weekday::Mon).unwrap()
+}
+
+fn iso_quarter_end(year: i32, quarter: u32) -> NaiveDate {
+    let next_start = if quarter < 4 {
+        iso_quarter_start(year, quarter + 1)
+    } else {
+        iso_quarter_start(year + 1, 1)
+    };
+
+    add_days(next_start, -1)
 }
 
 fn parse_iso_week_date(input: &str) -> Result<NaiveDate, String> {
@@ -150,27 +858,36 @@ fn parse_month_date(input: &str) -> Result<NaiveDate, String> {
     NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| format!("invalid month '{input}'"))
 }
 
-fn parse_quarter_date(input: &str) -> Result<NaiveDate, String> {
-    let regex = Regex::new(r"^(\d{4})-Q([1-4])$").unwrap();
+fn parse_quarter_date(input: &str, calendar: &Calendar) -> Result<NaiveDate, String> {
+    let regex = Regex::new(r"^(FY)?(\d{4})-Q([1-4])$").unwrap();
     let captures = regex
         .captures(input)
-        .ok_or_else(|| "expected --date in format 2026-Q1".to_string())?;
+        .ok_or_else(|| "expected --date in format 2026-Q1 or FY2026-Q1".to_string())?;
 
-    let year = captures[1]
+    let year = captures[2]
         .parse::<i32>()
         .map_err(|_| "invalid quarter year".to_string())?;
-    let quarter = captures[2]
+    let quarter = captures[3]
         .parse::<u32>()
         .map_err(|_| "invalid quarter value".to_string())?;
-    let month = match quarter {
-        1 => 1,
-        2 => 4,
-        3 => 7,
-        4 => 10,
-        _ => unreachable!(),
-    };
 
-    NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| format!("invalid quarter '{input}'"))
+    if captures.get(1).is_some() {
+        let fiscal_year_start = calendar
+            .fiscal_year_start(year)
+            .ok_or_else(|| format!("invalid quarter '{input}'"))?;
+        return Ok(add_months(fiscal_year_start, (quarter as i32 - 1) * 3));
+    }
+
+    match calendar.quarter_style {
+        Some(QuarterStyle::IsoWeek) => {
+            NaiveDate::from_isoywd_opt(year, (quarter - 1) * 13 + 1, Weekday::Mon)
+                .ok_or_else(|| format!("invalid quarter '{input}'"))
+        }
+        Some(QuarterStyle::Calendar) | None => {
+            NaiveDate::from_ymd_opt(year, (quarter - 1) * 3 + 1, 1)
+                .ok_or_else(|| format!("invalid quarter '{input}'"))
+        }
+    }
 }
 
 fn parse_year_date(input: &str) -> Result<NaiveDate, String> {
@@ -186,34 +903,71 @@ fn parse_year_date(input: &str) -> Result<NaiveDate, String> {
     NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(|| format!("invalid year '{input}'"))
 }
 
+fn parse_fiscal_year_date(input: &str, calendar: &Calendar) -> Result<NaiveDate, String> {
+    let regex = Regex::new(r"^FY(\d{4})$").unwrap();
+    let captures = regex
+        .captures(input)
+        .ok_or_else(|| "expected --date in format FY2026".to_string())?;
+
+    let year = captures[1]
+        .parse::<i32>()
+        .map_err(|_| "invalid fiscal year value".to_string())?;
+
+    calendar
+        .fiscal_year_start(year)
+        .ok_or_else(|| format!("invalid fiscal year '{input}'"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()
+    }
+
+    #[test]
+    fn parses_formatted_names_back_into_dates() {
+        let calendar = Calendar::default();
+        let names = ["2026-W01", "2025-W52", "notes"];
+
+        let parsed =
+            parse_formatted_dates(&names, "%G-W%V", JournalPeriod::Week, &calendar, today())
+                .unwrap();
+
+        assert_eq!(
+            parsed,
+            vec![
+                ("2026-W01", NaiveDate::from_ymd_opt(2025, 12, 29).unwrap()),
+                ("2025-W52", NaiveDate::from_ymd_opt(2025, 12, 22).unwrap()),
+            ]
+        );
+    }
+
     #[test]
     fn parses_iso_week_input_with_single_digit_week() {
-        let result = parse_reference_date_input("2026-W1").unwrap();
+        let result = parse_reference_date_input("2026-W1", today(), &Calendar::default()).unwrap();
 
         assert_eq!(result, NaiveDate::from_ymd_opt(2025, 12, 29).unwrap());
     }
 
     #[test]
     fn parses_month_input_to_first_day() {
-        let result = parse_reference_date_input("2026-04").unwrap();
+        let result = parse_reference_date_input("2026-04", today(), &Calendar::default()).unwrap();
 
         assert_eq!(result, NaiveDate::from_ymd_opt(2026, 4, 1).unwrap());
     }
 
     #[test]
     fn parses_year_input_to_first_day() {
-        let result = parse_reference_date_input("2026").unwrap();
+        let result = parse_reference_date_input("2026", today(), &Calendar::default()).unwrap();
 
         assert_eq!(result, NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
     }
 
     #[test]
     fn parses_quarter_input_to_first_day() {
-        let result = parse_reference_date_input("2026-Q2").unwrap();
+        let result = parse_reference_date_input("2026-Q2", today(), &Calendar::default()).unwrap();
 
         assert_eq!(result, NaiveDate::from_ymd_opt(2026, 4, 1).unwrap());
     }
@@ -221,10 +975,10 @@ mod tests {
     #[test]
     fn applies_offsets_with_calendar_months() {
         let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
-        let offset = parse_date_offset("+1 month").unwrap();
+        let offset = parse_date_offsets("+1 month").unwrap()[0];
 
         assert_eq!(
-            apply_date_offset(date, offset),
+            apply_date_offset(date, offset, &Calendar::default()),
             NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
         );
     }
@@ -233,41 +987,432 @@ mod tests {
     fn formats_quarter_from_reference_date() {
         let date = NaiveDate::from_ymd_opt(2026, 4, 19).unwrap();
 
-        assert_eq!(format_date(date, "%Y-Q%Q"), "2026-Q2");
+        assert_eq!(
+            format_date(date, "%Y-Q%Q", &Calendar::default()).unwrap(),
+            "2026-Q2"
+        );
     }
 
     #[test]
     fn applies_quarter_offsets_as_three_months() {
         let date = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
-        let offset = parse_date_offset("+1 quarter").unwrap();
+        let offset = parse_date_offsets("+1 quarter").unwrap()[0];
 
         assert_eq!(
-            apply_date_offset(date, offset),
+            apply_date_offset(date, offset, &Calendar::default()),
             NaiveDate::from_ymd_opt(2024, 4, 30).unwrap()
         );
     }
 
     #[test]
     fn resolves_reference_date_with_quarter_offset() {
-        let result = resolve_reference_date(Some("2026-04-19"), Some("-1 quarter")).unwrap();
+        let result = resolve_reference_date(
+            Some("2026-04-19"),
+            Some("-1 quarter"),
+            JournalPeriod::Day,
+            &Calendar::default(),
+            today(),
+        )
+        .unwrap();
 
         assert_eq!(result, NaiveDate::from_ymd_opt(2026, 1, 19).unwrap());
     }
 
     #[test]
     fn rejects_invalid_iso_week_input() {
-        let error = parse_reference_date_input("2026-W54").unwrap_err();
+        let error =
+            parse_reference_date_input("2026-W54", today(), &Calendar::default()).unwrap_err();
 
         assert_eq!(
             error,
-            "unsupported --date '2026-W54'. use YYYY-MM-DD, YYYY-W01, YYYY-Q1, YYYY-MM, or YYYY"
+            "unsupported --date '2026-W54'. use YYYY-MM-DD, YYYY-W01, YYYY-Q1, FYYYYY-Q1, YYYY-MM, YYYY, FYYYYY, or today, yesterday, tomorrow, last friday, next monday, +1 day, +1 weekday, +1 week -2 days, 3 days ago, in 2 weeks, or end of month"
         );
     }
 
     #[test]
     fn resolves_reference_date_with_offset() {
-        let result = resolve_reference_date(Some("2026-04-19"), Some("+1 year")).unwrap();
+        let result = resolve_reference_date(
+            Some("2026-04-19"),
+            Some("+1 year"),
+            JournalPeriod::Day,
+            &Calendar::default(),
+            today(),
+        )
+        .unwrap();
 
         assert_eq!(result, NaiveDate::from_ymd_opt(2027, 4, 19).unwrap());
     }
+
+    #[test]
+    fn infers_period_from_journal_format() {
+        assert_eq!(infer_period("%Y-%m-%d"), JournalPeriod::Day);
+        assert_eq!(infer_period("%G-W%V"), JournalPeriod::Week);
+        assert_eq!(infer_period("%Y-%m"), JournalPeriod::Month);
+        assert_eq!(infer_period("%Y-Q%Q"), JournalPeriod::Quarter);
+        assert_eq!(infer_period("%Y-H%EH"), JournalPeriod::HalfYear);
+        assert_eq!(infer_period("FY%FY-H%FH"), JournalPeriod::HalfYear);
+        assert_eq!(infer_period("%Y"), JournalPeriod::Year);
+        assert_eq!(infer_period("100%%"), JournalPeriod::Day);
+    }
+
+    #[test]
+    fn walks_half_years_in_six_month_steps() {
+        let calendar = Calendar::default();
+        let date = NaiveDate::from_ymd_opt(2026, 8, 10).unwrap();
+
+        assert_eq!(
+            period_start(date, JournalPeriod::HalfYear, &calendar),
+            NaiveDate::from_ymd_opt(2026, 7, 1).unwrap()
+        );
+        assert_eq!(
+            period_end(date, JournalPeriod::HalfYear, &calendar),
+            NaiveDate::from_ymd_opt(2026, 12, 31).unwrap()
+        );
+        assert_eq!(
+            resolve_reference_date(
+                Some("2026-08-10"),
+                Some("+1"),
+                JournalPeriod::HalfYear,
+                &calendar,
+                today()
+            )
+            .unwrap(),
+            NaiveDate::from_ymd_opt(2027, 1, 1).unwrap()
+        );
+        assert_eq!(
+            period_start(
+                date,
+                JournalPeriod::HalfYear,
+                &fiscal_calendar().for_format("FY%FY-H%FH")
+            ),
+            NaiveDate::from_ymd_opt(2026, 7, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn snaps_reference_date_to_period_start() {
+        let result = resolve_reference_date(
+            Some("2026-04-19"),
+            None,
+            JournalPeriod::Week,
+            &Calendar::default(),
+            today(),
+        )
+        .unwrap();
+
+        assert_eq!(result, NaiveDate::from_ymd_opt(2026, 4, 13).unwrap());
+    }
+
+    #[test]
+    fn bare_offset_moves_by_one_period() {
+        let result = resolve_reference_date(
+            Some("2026-04-19"),
+            Some("+1"),
+            JournalPeriod::Week,
+            &Calendar::default(),
+            today(),
+        )
+        .unwrap();
+
+        assert_eq!(result, NaiveDate::from_ymd_opt(2026, 4, 20).unwrap());
+    }
+
+    #[test]
+    fn day_offset_stays_within_weekly_period() {
+        let result = resolve_reference_date(
+            Some("2026-04-14"),
+            Some("+1 day"),
+            JournalPeriod::Week,
+            &Calendar::default(),
+            today(),
+        )
+        .unwrap();
+
+        assert_eq!(result, NaiveDate::from_ymd_opt(2026, 4, 13).unwrap());
+    }
+
+    #[test]
+    fn computes_period_end() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+
+        assert_eq!(
+            period_end(date, JournalPeriod::Month, &Calendar::default()),
+            NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()
+        );
+        assert_eq!(
+            period_end(date, JournalPeriod::Quarter, &Calendar::default()),
+            NaiveDate::from_ymd_opt(2024, 3, 31).unwrap()
+        );
+    }
+
+    fn fiscal_calendar() -> Calendar {
+        Calendar {
+            fiscal_year_start_month: 7,
+            ..Calendar::default()
+        }
+    }
+
+    #[test]
+    fn formats_fiscal_year_tokens() {
+        let date = NaiveDate::from_ymd_opt(2026, 8, 3).unwrap();
+
+        assert_eq!(
+            format_date(date, "FY%FY-Q%FQ (%Q)", &fiscal_calendar()).unwrap(),
+            "FY2027-Q1 (3)"
+        );
+        assert_eq!(
+            format_date(date, "FY%Fy", &fiscal_calendar()).unwrap(),
+            "FY27"
+        );
+    }
+
+    #[test]
+    fn parses_fiscal_quarter_input() {
+        let result = parse_reference_date_input("FY2027-Q3", today(), &fiscal_calendar()).unwrap();
+
+        assert_eq!(result, NaiveDate::from_ymd_opt(2027, 1, 1).unwrap());
+    }
+
+    #[test]
+    fn calendar_quarters_differ_from_iso_week_quarters_at_year_boundary() {
+        let date = NaiveDate::from_ymd_opt(2027, 1, 1).unwrap();
+        let months = Calendar {
+            quarter_style: Some(QuarterStyle::Calendar),
+            ..Calendar::default()
+        };
+        let iso_week = Calendar {
+            quarter_style: Some(QuarterStyle::IsoWeek),
+            ..Calendar::default()
+        };
+
+        assert_eq!(format_date(date, "%Y-Q%Q", &months).unwrap(), "2027-Q1");
+        assert_eq!(format_date(date, "%Y-Q%Q", &iso_week).unwrap(), "2027-Q4");
+        assert_eq!(
+            format_date(date, "%Y-Q%Q", &Calendar::default()).unwrap(),
+            "2027-Q4"
+        );
+    }
+
+    #[test]
+    fn iso_week_quarter_offsets_move_between_quarters() {
+        let iso_week = Calendar {
+            quarter_style: Some(QuarterStyle::IsoWeek),
+            ..Calendar::default()
+        };
+        let result = resolve_reference_date(
+            Some("2026-Q4"),
+            Some("+1 quarter"),
+            JournalPeriod::Quarter,
+            &iso_week,
+            today(),
+        )
+        .unwrap();
+
+        assert_eq!(result, NaiveDate::from_ymd_opt(2027, 1, 4).unwrap());
+    }
+
+    #[test]
+    fn snaps_weeks_to_configured_week_start() {
+        let sunday = Calendar {
+            week_start: Weekday::Sun,
+            ..Calendar::default()
+        };
+        let date = NaiveDate::from_ymd_opt(2026, 4, 22).unwrap();
+
+        assert_eq!(
+            period_start(date, JournalPeriod::Week, &sunday),
+            NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()
+        );
+    }
+
+    #[test]
+    fn snaps_years_to_fiscal_year_start() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+
+        assert_eq!(
+            period_start(
+                date,
+                JournalPeriod::Year,
+                &fiscal_calendar().for_format("FY%FY")
+            ),
+            NaiveDate::from_ymd_opt(2025, 7, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn snaps_calendar_formats_to_calendar_boundaries() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 15).unwrap();
+        let february = Calendar {
+            fiscal_year_start_month: 2,
+            ..Calendar::default()
+        };
+
+        assert_eq!(
+            period_start(
+                date,
+                JournalPeriod::Year,
+                &fiscal_calendar().for_format("%Y")
+            ),
+            NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+        );
+        assert_eq!(
+            period_start(
+                NaiveDate::from_ymd_opt(2026, 1, 15).unwrap(),
+                JournalPeriod::Quarter,
+                &february.for_format("%Y-Q%Q")
+            ),
+            NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+        );
+        assert_eq!(
+            period_start(
+                date,
+                JournalPeriod::Quarter,
+                &february.for_format("FY%FY-Q%FQ")
+            ),
+            NaiveDate::from_ymd_opt(2026, 2, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn formats_week_of_month_and_ordinals() {
+        let date = NaiveDate::from_ymd_opt(2026, 4, 22).unwrap();
+
+        assert_eq!(
+            format_date(date, "%o, week %EW", &Calendar::default()).unwrap(),
+            "22nd, week 4"
+        );
+    }
+
+    #[test]
+    fn formats_localized_names() {
+        let calendar = Calendar {
+            locale: Some(Locale::pt_BR),
+            ..Calendar::default()
+        };
+        let date = NaiveDate::from_ymd_opt(2026, 4, 19).unwrap();
+
+        assert_eq!(
+            format_date(date, "%EA, %-d de %EB", &calendar).unwrap(),
+            "domingo, 19 de abril"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_format_tokens() {
+        let date = NaiveDate::from_ymd_opt(2026, 4, 19).unwrap();
+        let error = format_date(date, "%Y-%J", &Calendar::default()).unwrap_err();
+
+        assert_eq!(error, "unknown date format token '%J' in '%Y-%J'");
+    }
+
+    #[test]
+    fn rejects_time_tokens_for_dates() {
+        let date = NaiveDate::from_ymd_opt(2026, 4, 19).unwrap();
+
+        assert!(format_date(date, "%H:%M", &Calendar::default()).is_err());
+    }
+
+    #[test]
+    fn parses_natural_language_reference_dates() {
+        let test_cases = [
+            ("yesterday", (2026, 4, 18)),
+            ("Tomorrow", (2026, 4, 20)),
+            ("last friday", (2026, 4, 17)),
+            ("next monday", (2026, 4, 20)),
+            ("next sunday", (2026, 4, 26)),
+            ("3 days ago", (2026, 4, 16)),
+            ("in 2 weeks", (2026, 5, 3)),
+            ("end of month", (2026, 4, 30)),
+            ("start of week", (2026, 4, 13)),
+            ("last month", (2026, 3, 19)),
+        ];
+
+        for (input, (year, month, day)) in test_cases {
+            let result = parse_reference_date_input(input, today(), &Calendar::default()).unwrap();
+
+            assert_eq!(
+                result,
+                NaiveDate::from_ymd_opt(year, month, day).unwrap(),
+                "Failed on input: {input}"
+            );
+        }
+    }
+
+    #[test]
+    fn applies_compound_offsets_in_order() {
+        let result =
+            resolve_relative_date(today(), "+1 week -2 days", &Calendar::default()).unwrap();
+
+        assert_eq!(result, NaiveDate::from_ymd_opt(2026, 4, 24).unwrap());
+    }
+
+    #[test]
+    fn rejects_offsets_with_trailing_garbage() {
+        assert!(parse_date_offsets("+1 week soon").is_err());
+        assert!(parse_date_offsets("+1 fortnight").is_err());
+    }
+
+    #[test]
+    fn weekday_offsets_skip_weekends() {
+        let monday = NaiveDate::from_ymd_opt(2026, 4, 20).unwrap();
+
+        assert_eq!(
+            resolve_relative_date(monday, "-1 weekday", &Calendar::default()).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 4, 17).unwrap()
+        );
+        assert_eq!(
+            resolve_relative_date(today(), "+1 business day", &Calendar::default()).unwrap(),
+            monday
+        );
+    }
+
+    #[test]
+    fn weekday_offsets_skip_holidays() {
+        let calendar = Calendar {
+            holidays: BTreeSet::from([NaiveDate::from_ymd_opt(2026, 4, 17).unwrap()]),
+            ..Calendar::default()
+        };
+        let monday = NaiveDate::from_ymd_opt(2026, 4, 20).unwrap();
+
+        assert_eq!(
+            resolve_relative_date(monday, "last weekday", &calendar).unwrap(),
+            NaiveDate::from_ymd_opt(2026, 4, 16).unwrap()
+        );
+    }
+
+    #[test]
+    fn parses_explicit_date_range() {
+        let (start, end) =
+            parse_date_range("2026-04-20..2026-04-24", today(), &Calendar::default()).unwrap();
+
+        assert_eq!(start, NaiveDate::from_ymd_opt(2026, 4, 20).unwrap());
+        assert_eq!(end, NaiveDate::from_ymd_opt(2026, 4, 24).unwrap());
+    }
+
+    #[test]
+    fn expands_single_anchor_into_its_span() {
+        let (start, end) = parse_date_range("2026-W17", today(), &Calendar::default()).unwrap();
+        let days = period_starts_between(start, end, JournalPeriod::Day, &Calendar::default());
+
+        assert_eq!(days.len(), 7);
+        assert_eq!(days[0], NaiveDate::from_ymd_opt(2026, 4, 20).unwrap());
+        assert_eq!(days[6], NaiveDate::from_ymd_opt(2026, 4, 26).unwrap());
+    }
+
+    #[test]
+    fn lists_each_overlapping_period_once() {
+        let (start, end) = parse_date_range("2026-04", today(), &Calendar::default()).unwrap();
+        let weeks = period_starts_between(start, end, JournalPeriod::Week, &Calendar::default());
+
+        assert_eq!(
+            weeks.first(),
+            Some(&NaiveDate::from_ymd_opt(2026, 3, 30).unwrap())
+        );
+        assert_eq!(weeks.len(), 5);
+    }
+
+    #[test]
+    fn rejects_reversed_ranges() {
+        assert!(parse_date_range("2026-04-24..2026-04-20", today(), &Calendar::default()).is_err());
+    }
 }
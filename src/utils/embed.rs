@@ -0,0 +1,51 @@
+use std::{fs, io, path::Path};
+
+use chrono::NaiveDate;
+
+use crate::utils::{
+    date::Calendar,
+    markdown::section_body,
+    resolve_note_path,
+    template::{journal_entries_on_disk, specific_template_info},
+};
+
+/// The section of a note, or its whole body when `section` is `None`.
+pub fn embed_note(name: &str, section: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+    let path = resolve_note_path(name)?;
+    let contents = fs::read_to_string(&path)?;
+
+    Ok(section_body(&contents, section).unwrap_or_default())
+}
+
+/// The section of every entry of `journal` between `start` and `end`, oldest first, each one
+/// under a link to the entry it comes from. Entries without the section are skipped.
+pub fn embed_journal(
+    journal: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+    section: Option<&str>,
+    calendar: &Calendar,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let journal = specific_template_info(journal)?;
+    let mut blocks = Vec::new();
+
+    for (date, path) in journal_entries_on_disk(&journal, calendar, start)? {
+        if date < start || date > end {
+            continue;
+        }
+
+        let contents = fs::read_to_string(&path)?;
+        let Some(body) = section_body(&contents, section).filter(|body| !body.is_empty()) else {
+            continue;
+        };
+
+        let name = Path::new(&path)
+            .file_stem()
+            .ok_or_else(|| io::Error::other(format!("invalid journal entry path: {path}")))?
+            .to_string_lossy()
+            .into_owned();
+        blocks.push(format!("[[{name}]]\n{body}"));
+    }
+
+    Ok(blocks.join("\n\n"))
+}
@@ -0,0 +1,428 @@
+use std::io;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, SecondsFormat};
+
+use crate::{
+    config::{Config, FrontMatterFormat},
+    utils::{
+        markdown::{front_matter_end, front_matter_entries, front_matter_list},
+        placeholder::TemplateContext,
+    },
+};
+
+/// The fields written when `front_matter_fields` isn't configured, in the order they appear.
+pub const FRONT_MATTER_FIELDS: [&str; 7] = [
+    "title",
+    "created",
+    "template",
+    "journal",
+    "reference_date",
+    "aliases",
+    "tags",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FrontMatterValue {
+    Text(String),
+    Date(NaiveDate),
+    Timestamp(DateTime<FixedOffset>),
+    List(Vec<String>),
+}
+
+/// Adds the configured front matter to a freshly rendered note, leaving it untouched when
+/// `front_matter` isn't set in `[general]`.
+pub fn add_front_matter(
+    contents: String,
+    context: &TemplateContext,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let general = Config::read()?.general;
+    let Some(format) = general.front_matter else {
+        return Ok(contents);
+    };
+
+    let names = general.front_matter_fields.unwrap_or_else(|| {
+        FRONT_MATTER_FIELDS
+            .iter()
+            .map(|name| name.to_string())
+            .collect()
+    });
+    let fields = front_matter_fields(context, &names).map_err(io::Error::other)?;
+
+    Ok(merge_front_matter(&contents, &fields, format))
+}
+
+/// The values of `names` for the note being created, skipping the ones that don't apply to it,
+/// such as `journal` for a regular note.
+pub fn front_matter_fields(
+    context: &TemplateContext,
+    names: &[String],
+) -> Result<Vec<(String, FrontMatterValue)>, String> {
+    let mut fields = Vec::new();
+
+    for name in names {
+        let value = match name.as_str() {
+            "title" => Some(FrontMatterValue::Text(context.title.clone())),
+            "created" => Some(FrontMatterValue::Timestamp(context.now)),
+            "template" => context.template.clone().map(FrontMatterValue::Text),
+            "journal" => context
+                .journal
+                .as_ref()
+                .map(|journal| FrontMatterValue::Text(journal.name.clone())),
+            "reference_date" => context
+                .journal
+                .as_ref()
+                .map(|_| FrontMatterValue::Date(context.reference_date)),
+            "aliases" => (!context.aliases.is_empty())
+                .then(|| FrontMatterValue::List(context.aliases.clone())),
+            "tags" => {
+                (!context.tags.is_empty()).then(|| FrontMatterValue::List(context.tags.clone()))
+            }
+            _ => {
+                return Err(format!(
+                    "unknown front matter field '{name}'. accepted fields are {}",
+                    FRONT_MATTER_FIELDS.join(", ")
+                ))
+            }
+        };
+
+        if let Some(value) = value {
+            fields.push((name.clone(), value));
+        }
+    }
+
+    Ok(fields)
+}
+
+/// Adds `fields` to the front matter of `contents`. Keys the template already sets are kept as
+/// written, and a template without front matter gets a new block in `format`.
+pub fn merge_front_matter(
+    contents: &str,
+    fields: &[(String, FrontMatterValue)],
+    format: FrontMatterFormat,
+) -> String {
+    add_fields(contents, fields, format, false)
+}
+
+/// Like `merge_front_matter`, but adds the missing keys after the ones the note already has.
+pub fn append_front_matter(
+    contents: &str,
+    fields: &[(String, FrontMatterValue)],
+    format: FrontMatterFormat,
+) -> String {
+    add_fields(contents, fields, format, true)
+}
+
+fn add_fields(
+    contents: &str,
+    fields: &[(String, FrontMatterValue)],
+    format: FrontMatterFormat,
+    after_existing: bool,
+) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let end = front_matter_end(&lines);
+
+    if end == 0 {
+        if fields.is_empty() {
+            return contents.to_owned();
+        }
+
+        let delimiter = delimiter(format);
+        let mut block = vec![delimiter.to_owned()];
+        block.extend(
+            fields
+                .iter()
+                .map(|(name, value)| render_field(name, value, format)),
+        );
+        block.push(delimiter.to_owned());
+
+        return format!("{}\n{contents}", block.join("\n"));
+    }
+
+    let format = if lines[0].trim_end() == "+++" {
+        FrontMatterFormat::Toml
+    } else {
+        FrontMatterFormat::Yaml
+    };
+    let existing: Vec<String> = front_matter_entries(contents)
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    let added: Vec<String> = fields
+        .iter()
+        .filter(|(name, _)| !existing.contains(name))
+        .map(|(name, value)| render_field(name, value, format))
+        .collect();
+
+    if added.is_empty() {
+        return contents.to_owned();
+    }
+
+    let at = if after_existing { end - 1 } else { 1 };
+    let mut merged: Vec<&str> = lines[..at].to_vec();
+    merged.extend(added.iter().map(String::as_str));
+    merged.extend(&lines[at..]);
+
+    let mut merged = merged.join("\n");
+    if contents.ends_with('\n') {
+        merged.push('\n');
+    }
+    merged
+}
+
+/// Splits the front matter off `contents`, with its values read back as the types
+/// `merge_front_matter` writes and the format of the block, `None` when there is no block.
+pub fn split_front_matter(
+    contents: &str,
+) -> (
+    Vec<(String, FrontMatterValue)>,
+    Option<FrontMatterFormat>,
+    String,
+) {
+    let lines: Vec<&str> = contents.lines().collect();
+    let end = front_matter_end(&lines);
+    if end == 0 {
+        return (Vec::new(), None, contents.to_owned());
+    }
+
+    let format = if lines[0].trim_end() == "+++" {
+        FrontMatterFormat::Toml
+    } else {
+        FrontMatterFormat::Yaml
+    };
+    let fields = front_matter_entries(contents)
+        .into_iter()
+        .map(|(name, value)| {
+            let value = if value.starts_with('[') {
+                FrontMatterValue::List(front_matter_list(&value))
+            } else if let Ok(date) = NaiveDate::parse_from_str(&value, "%Y-%m-%d") {
+                FrontMatterValue::Date(date)
+            } else if let Ok(timestamp) = DateTime::parse_from_rfc3339(&value) {
+                FrontMatterValue::Timestamp(timestamp)
+            } else {
+                FrontMatterValue::Text(value)
+            };
+            (name, value)
+        })
+        .collect();
+
+    let mut body = lines[end..].join("\n");
+    if contents.ends_with('\n') && !body.is_empty() {
+        body.push('\n');
+    }
+    (fields, Some(format), body)
+}
+
+/// `contents` without the `name` key of its front matter, dropping the block when nothing else
+/// is left in it.
+pub fn remove_front_matter_key(contents: &str, name: &str) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let end = front_matter_end(&lines);
+    if end == 0 {
+        return contents.to_owned();
+    }
+
+    let block: Vec<&str> = lines[1..end - 1]
+        .iter()
+        .filter(|line| {
+            line.find([':', '='])
+                .map_or(true, |separator| line[..separator].trim() != name)
+        })
+        .copied()
+        .collect();
+
+    let mut kept: Vec<&str> = Vec::new();
+    if !block.is_empty() {
+        kept.push(lines[0]);
+        kept.extend(block);
+        kept.push(lines[end - 1]);
+    }
+    kept.extend(&lines[end..]);
+
+    let mut updated = kept.join("\n");
+    if contents.ends_with('\n') && !updated.is_empty() {
+        updated.push('\n');
+    }
+    updated
+}
+
+fn delimiter(format: FrontMatterFormat) -> &'static str {
+    match format {
+        FrontMatterFormat::Yaml => "---",
+        FrontMatterFormat::Toml => "+++",
+    }
+}
+
+fn render_field(name: &str, value: &FrontMatterValue, format: FrontMatterFormat) -> String {
+    let value = match value {
+        FrontMatterValue::Text(text) => render_string(text, format),
+        FrontMatterValue::Date(date) => date.format("%Y-%m-%d").to_string(),
+        FrontMatterValue::Timestamp(timestamp) => {
+            timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+        }
+        FrontMatterValue::List(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|item| render_string(item, format))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+
+    match format {
+        FrontMatterFormat::Yaml => format!("{name}: {value}"),
+        FrontMatterFormat::Toml => format!("{name} = {value}"),
+    }
+}
+
+/// TOML strings are always quoted, YAML ones only when they could be read as another type, such
+/// as a number or a date, or would break the syntax.
+fn render_string(text: &str, format: FrontMatterFormat) -> String {
+    let plain = !text.is_empty()
+        && text
+            .chars()
+            .all(|char| char.is_alphanumeric() || " -_./".contains(char))
+        && !text.starts_with(|char: char| char.is_ascii_digit() || "-. ".contains(char))
+        && !text.ends_with(' ')
+        && !["true", "false", "yes", "no", "null", "~"].contains(&text.to_lowercase().as_str());
+
+    if format == FrontMatterFormat::Yaml && plain {
+        return text.to_owned();
+    }
+
+    toml::Value::String(text.to_owned()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields() -> Vec<(String, FrontMatterValue)> {
+        vec![
+            (
+                String::from("title"),
+                FrontMatterValue::Text(String::from("2026-04-19")),
+            ),
+            (
+                String::from("created"),
+                FrontMatterValue::Timestamp(
+                    DateTime::parse_from_rfc3339("2026-04-19T08:30:00Z").unwrap(),
+                ),
+            ),
+            (
+                String::from("journal"),
+                FrontMatterValue::Text(String::from("day")),
+            ),
+            (
+                String::from("tags"),
+                FrontMatterValue::List(vec![String::from("daily"), String::from("q2: plan")]),
+            ),
+        ]
+    }
+
+    #[test]
+    fn writes_a_new_block_in_the_configured_format() {
+        assert_eq!(
+            merge_front_matter("# Today\n", &fields(), FrontMatterFormat::Yaml),
+            "---\ntitle: \"2026-04-19\"\ncreated: 2026-04-19T08:30:00Z\njournal: day\n\
+             tags: [daily, \"q2: plan\"]\n---\n# Today\n"
+        );
+        assert_eq!(
+            merge_front_matter("# Today\n", &fields(), FrontMatterFormat::Toml),
+            "+++\ntitle = \"2026-04-19\"\ncreated = 2026-04-19T08:30:00Z\njournal = \"day\"\n\
+             tags = [\"daily\", \"q2: plan\"]\n+++\n# Today\n"
+        );
+    }
+
+    #[test]
+    fn keeps_the_keys_and_format_of_the_template() {
+        let template = "+++\ntags = [\"meeting\"]\n+++\n# Today\n";
+
+        assert_eq!(
+            merge_front_matter(template, &fields(), FrontMatterFormat::Yaml),
+            "+++\ntitle = \"2026-04-19\"\ncreated = 2026-04-19T08:30:00Z\njournal = \"day\"\n\
+             tags = [\"meeting\"]\n+++\n# Today\n"
+        );
+    }
+
+    #[test]
+    fn appends_missing_keys_after_the_existing_ones() {
+        let fields = [
+            (
+                String::from("title"),
+                FrontMatterValue::Text("Other".into()),
+            ),
+            (
+                String::from("tags"),
+                FrontMatterValue::List(vec!["meeting".into()]),
+            ),
+        ];
+
+        assert_eq!(
+            append_front_matter(
+                "---
+title: A
+status: open
+---
+# A
+",
+                &fields,
+                FrontMatterFormat::Yaml
+            ),
+            "---
+title: A
+status: open
+tags: [meeting]
+---
+# A
+"
+        );
+    }
+
+    #[test]
+    fn removes_a_key_and_the_block_it_leaves_empty() {
+        assert_eq!(
+            remove_front_matter_key(
+                "---\ntags: [a]\narchived: 2026-04-19\n---\n# A\n",
+                "archived"
+            ),
+            "---\ntags: [a]\n---\n# A\n"
+        );
+        assert_eq!(
+            remove_front_matter_key("---\narchived: 2026-04-19\n---\n# A\n", "archived"),
+            "# A\n"
+        );
+    }
+
+    #[test]
+    fn splits_front_matter_into_typed_fields() {
+        let (fields, format, body) =
+            split_front_matter("---\ntags: [meeting]\ndue: 2026-04-24\n---\n### Meeting\n");
+
+        assert_eq!(
+            fields,
+            vec![
+                (
+                    String::from("tags"),
+                    FrontMatterValue::List(vec![String::from("meeting")])
+                ),
+                (
+                    String::from("due"),
+                    FrontMatterValue::Date(NaiveDate::from_ymd_opt(2026, 4, 24).unwrap())
+                ),
+            ]
+        );
+        assert_eq!(format, Some(FrontMatterFormat::Yaml));
+        assert_eq!(body, "### Meeting\n");
+    }
+
+    #[test]
+    fn rejects_unknown_fields() {
+        let context = TemplateContext::new(
+            String::from("Note"),
+            NaiveDate::from_ymd_opt(2026, 4, 19).unwrap(),
+        );
+
+        assert!(front_matter_fields(&context, &[String::from("author")]).is_err());
+    }
+}
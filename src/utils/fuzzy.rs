@@ -0,0 +1,74 @@
+/// Scores how well `query` matches `candidate` as a case-insensitive subsequence, or `None` when
+/// some query character is missing. Consecutive characters, characters at the start of a word
+/// and matches at the very start of the candidate score higher.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let candidate: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut query_index = 0;
+    let mut previous_match: Option<usize> = None;
+
+    for (index, char) in candidate.iter().enumerate() {
+        if query_index == query.len() {
+            break;
+        }
+        if *char != query[query_index] {
+            continue;
+        }
+
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == index) {
+            score += 4;
+        }
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+
+        previous_match = Some(index);
+        query_index += 1;
+    }
+
+    if query_index < query.len() {
+        return None;
+    }
+
+    if candidate == query {
+        score += 20;
+    } else if candidate.starts_with(&query) {
+        score += 10;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requires_every_query_character_in_order() {
+        assert!(fuzzy_score("pk", "Project Kickoff").is_some());
+        assert!(fuzzy_score("kp", "Project Kickoff").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+
+    #[test]
+    fn ranks_exact_prefix_and_word_starts_higher() {
+        let exact = fuzzy_score("ada", "ada").unwrap();
+        let prefix = fuzzy_score("ada", "ada-lovelace").unwrap();
+        let words = fuzzy_score("al", "ada-lovelace").unwrap();
+        let scattered = fuzzy_score("al", "canal").unwrap();
+
+        assert!(exact > prefix);
+        assert!(words > scattered);
+    }
+}
@@ -0,0 +1,85 @@
+use std::{collections::BTreeSet, fs, path::Path};
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct HolidayFile {
+    holidays: Vec<String>,
+}
+
+/// Loads holidays from a TOML file with a `holidays = ["2026-12-25"]` list, or from an ICS
+/// calendar, in which case the start date of every event is a holiday.
+pub fn load_holidays(path: &str) -> Result<BTreeSet<NaiveDate>, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| format!("failed to read holidays file '{path}': {err}"))?;
+
+    let is_ics = Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ics"));
+
+    if is_ics {
+        parse_ics_holidays(&contents)
+    } else {
+        parse_toml_holidays(&contents)
+    }
+    .map_err(|err| format!("invalid holidays file '{path}': {err}"))
+}
+
+fn parse_toml_holidays(contents: &str) -> Result<BTreeSet<NaiveDate>, String> {
+    let file: HolidayFile = toml::from_str(contents).map_err(|err| err.to_string())?;
+
+    file.holidays
+        .iter()
+        .map(|date| {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| format!("expected YYYY-MM-DD, found '{date}'"))
+        })
+        .collect()
+}
+
+fn parse_ics_holidays(contents: &str) -> Result<BTreeSet<NaiveDate>, String> {
+    contents
+        .lines()
+        .filter(|line| line.starts_with("DTSTART"))
+        .map(|line| {
+            let value = line.rsplit(':').next().unwrap_or_default().trim();
+            let date = value.get(..8).unwrap_or(value);
+
+            NaiveDate::parse_from_str(date, "%Y%m%d")
+                .map_err(|_| format!("unsupported DTSTART value '{value}'"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_toml_holiday_list() {
+        let holidays = parse_toml_holidays(r#"holidays = ["2026-12-25", "2027-01-01"]"#).unwrap();
+
+        assert_eq!(
+            holidays.into_iter().collect::<Vec<_>>(),
+            vec![
+                NaiveDate::from_ymd_opt(2026, 12, 25).unwrap(),
+                NaiveDate::from_ymd_opt(2027, 1, 1).unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_ics_event_start_dates() {
+        let ics = "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20261225\r\nSUMMARY:Christmas\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART:20270101T000000Z\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+        let holidays = parse_ics_holidays(ics).unwrap();
+
+        assert!(holidays.contains(&NaiveDate::from_ymd_opt(2026, 12, 25).unwrap()));
+        assert!(holidays.contains(&NaiveDate::from_ymd_opt(2027, 1, 1).unwrap()));
+    }
+
+    #[test]
+    fn rejects_malformed_toml_dates() {
+        assert!(parse_toml_holidays(r#"holidays = ["25/12/2026"]"#).is_err());
+    }
+}
@@ -0,0 +1,306 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use chrono::{DateTime, Utc};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{
+    links::{extract_links, Link},
+    markdown::{front_matter_entries, headings, note_tags},
+    markdown_files,
+    vault::{archive_folder, vault_folders},
+    write_atomically,
+};
+
+/// Bumped whenever the stored fields change, so older indexes are rebuilt instead of misread.
+const INDEX_VERSION: u32 = 3;
+
+/// What the index knows about a note, refreshed whenever its size or modification time change.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexedNote {
+    pub path: String,
+    pub name: String,
+    pub title: String,
+    pub headings: Vec<String>,
+    pub tags: Vec<String>,
+    pub links: Vec<Link>,
+    pub front_matter: BTreeMap<String, String>,
+    pub modified: u64,
+    pub size: u64,
+}
+
+impl IndexedNote {
+    pub fn parse(path: &Path, contents: &str, modified: u64, size: u64) -> Self {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let front_matter: BTreeMap<String, String> =
+            front_matter_entries(contents).into_iter().collect();
+        let title = front_matter
+            .get("title")
+            .cloned()
+            .or_else(|| {
+                contents
+                    .lines()
+                    .find_map(|line| line.strip_prefix("# "))
+                    .map(|title| title.trim().to_string())
+            })
+            .unwrap_or_else(|| name.clone());
+
+        Self {
+            path: path.to_string_lossy().into_owned(),
+            name,
+            title,
+            headings: headings(contents),
+            tags: note_tags(contents),
+            links: extract_links(contents),
+            front_matter,
+            modified,
+            size,
+        }
+    }
+}
+
+/// How many notes a refresh added, re-read and dropped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct IndexChanges {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+impl IndexChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added + self.updated + self.removed == 0
+    }
+}
+
+/// The on-disk index of the note and journal folders, kept in the cache directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NoteIndex {
+    pub version: u32,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub notes: BTreeMap<String, IndexedNote>,
+}
+
+impl NoteIndex {
+    /// Loads the index of the configured folders and brings it up to date with the files. Every
+    /// file is still listed and checked, only the unchanged ones skip being read and parsed.
+    pub fn open() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = index_path()?;
+        let mut index = Self::load(&path);
+
+        if !index.refresh(&vault_files()?).is_empty() {
+            index.save(&path)?;
+        }
+
+        Ok(index)
+    }
+
+    /// Discards the stored index and indexes every note again.
+    pub fn rebuild() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = index_path()?;
+        let mut index = Self {
+            version: INDEX_VERSION,
+            ..Self::default()
+        };
+
+        index.refresh(&vault_files()?);
+        index.save(&path)?;
+        Ok(index)
+    }
+
+    /// The stored index, or an empty one when it is missing, unreadable or outdated.
+    pub fn load(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Self>(&contents).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or(Self {
+                version: INDEX_VERSION,
+                ..Self::default()
+            })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        write_atomically(&path.to_string_lossy(), &serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Re-reads the files whose size or modification time changed and drops the ones that are
+    /// gone.
+    pub fn refresh(&mut self, files: &[PathBuf]) -> IndexChanges {
+        let mut changes = IndexChanges::default();
+        let current: BTreeSet<String> = files
+            .iter()
+            .map(|file| file.to_string_lossy().into_owned())
+            .collect();
+
+        self.notes.retain(|path, _| {
+            let keep = current.contains(path);
+            if !keep {
+                changes.removed += 1;
+            }
+            keep
+        });
+
+        for file in files {
+            let Ok(metadata) = fs::metadata(file) else {
+                continue;
+            };
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |modified| modified.as_nanos() as u64);
+            let size = metadata.len();
+
+            let key = file.to_string_lossy().into_owned();
+            let existing = self.notes.get(&key);
+            if existing.is_some_and(|note| note.modified == modified && note.size == size) {
+                continue;
+            }
+
+            let Ok(contents) = fs::read_to_string(file) else {
+                continue;
+            };
+            if existing.is_some() {
+                changes.updated += 1;
+            } else {
+                changes.added += 1;
+            }
+
+            self.notes
+                .insert(key, IndexedNote::parse(file, &contents, modified, size));
+        }
+
+        if !changes.is_empty() || self.updated_at.is_none() {
+            self.updated_at = Some(Utc::now());
+        }
+
+        changes
+    }
+}
+
+/// Where the index of the configured folders is stored. Each set of folders gets its own file,
+/// so configs passed with `--config` don't overwrite each other's index. The name hashes the
+/// canonical folder paths with FNV-1a, which unlike `DefaultHasher` stays the same across Rust
+/// releases.
+pub fn index_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dirs =
+        ProjectDirs::from("", "", "zapnote").ok_or("could not determine the cache directory")?;
+
+    let mut folders = Vec::new();
+    for folder in vault_folders()? {
+        let folder = fs::canonicalize(&folder).unwrap_or(folder);
+        folders.extend_from_slice(folder.to_string_lossy().as_bytes());
+        folders.push(0);
+    }
+
+    Ok(dirs
+        .cache_dir()
+        .join(format!("index-{:016x}.json", fnv1a(&folders))))
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Every Markdown file in the note and journal folders, leaving out the archive folder.
+pub fn vault_files() -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let archive = archive_folder()?;
+    let mut files: Vec<PathBuf> = vault_folders()?
+        .iter()
+        .flat_map(|folder| markdown_files(folder))
+        .filter(|file| !file.starts_with(&archive))
+        .collect();
+    files.sort();
+    files.dedup();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn hashes_folders_with_fnv1a() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn parses_title_headings_tags_and_front_matter() {
+        let contents =
+            "---\ntemplate: person\ntags: [people]\n---\n# Ada Lovelace\n## Work #math\n[[Babbage]]\n";
+        let note = IndexedNote::parse(Path::new("/notes/ada.md"), contents, 1, 2);
+
+        assert_eq!(note.name, "ada");
+        assert_eq!(note.title, "Ada Lovelace");
+        assert_eq!(note.headings, vec!["Ada Lovelace", "Work #math"]);
+        assert_eq!(note.tags, vec!["people"]);
+        assert_eq!(note.links[0].name, "Babbage");
+        assert_eq!(note.front_matter.get("template").unwrap(), "person");
+    }
+
+    #[test]
+    fn refresh_only_rereads_changed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let first = temp_dir.path().join("first.md");
+        let second = temp_dir.path().join("second.md");
+        fs::write(&first, "# First").unwrap();
+        fs::write(&second, "# Second").unwrap();
+
+        let mut index = NoteIndex::default();
+        let changes = index.refresh(&[first.clone(), second.clone()]);
+        assert_eq!(changes.added, 2);
+
+        assert!(index.refresh(&[first.clone(), second.clone()]).is_empty());
+
+        fs::write(&first, "# First, edited").unwrap();
+        let changes = index.refresh(std::slice::from_ref(&first));
+
+        assert_eq!(
+            changes,
+            IndexChanges {
+                added: 0,
+                updated: 1,
+                removed: 1
+            }
+        );
+        assert_eq!(
+            index.notes[&first.to_string_lossy().into_owned()].title,
+            "First, edited"
+        );
+    }
+
+    #[test]
+    fn saved_index_loads_back() {
+        let temp_dir = TempDir::new().unwrap();
+        let note = temp_dir.path().join("note.md");
+        fs::write(&note, "#tag").unwrap();
+
+        let mut index = NoteIndex::load(&temp_dir.path().join("missing.json"));
+        index.refresh(&[note]);
+        let path = temp_dir.path().join("cache").join("index.json");
+        index.save(&path).unwrap();
+
+        let loaded = NoteIndex::load(&path);
+        assert_eq!(loaded.version, INDEX_VERSION);
+        assert_eq!(loaded.notes, index.notes);
+    }
+}
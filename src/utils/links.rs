@@ -0,0 +1,349 @@
+use std::{collections::HashMap, path::Path, sync::OnceLock};
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{casing::normalized_name, index::NoteIndex};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    Wiki,
+    Markdown,
+}
+
+/// A link as written in a note, e.g. `[[Ada Lovelace|Ada]]` or `[Ada](ada-lovelace.md)`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    pub kind: LinkKind,
+    /// The target as written, without alias or heading, e.g. `Ada Lovelace` or `ada-lovelace.md`.
+    pub target: String,
+    /// The note name the target points to, e.g. `Ada Lovelace` or `ada-lovelace`.
+    pub name: String,
+    pub line: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NoteFile {
+    pub name: String,
+    pub path: String,
+    /// The name as compared with link names, see `normalized_name`.
+    #[serde(skip)]
+    pub key: String,
+}
+
+impl NoteFile {
+    pub fn new(name: String, path: String) -> Self {
+        Self {
+            key: normalized_name(&name),
+            name,
+            path,
+        }
+    }
+}
+
+/// A link found in `source`, one of the indexed notes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SourcedLink {
+    pub source: NoteFile,
+    #[serde(flatten)]
+    pub link: Link,
+    /// The name of the target as compared with note names, see `normalized_name`.
+    #[serde(skip)]
+    pub key: String,
+}
+
+/// Every note in the note and journal folders together with the links they contain.
+#[derive(Debug, Default)]
+pub struct LinkIndex {
+    pub notes: Vec<NoteFile>,
+    pub links: Vec<SourcedLink>,
+    /// The position in `notes` of the first note with each key.
+    by_key: HashMap<String, usize>,
+}
+
+impl LinkIndex {
+    /// The links of the configured note and journal folders, read from the note index.
+    pub fn build() -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self::from_index(&NoteIndex::open()?))
+    }
+
+    pub fn from_index(index: &NoteIndex) -> Self {
+        let mut links = Self::default();
+
+        for indexed in index.notes.values() {
+            let note = NoteFile::new(indexed.name.clone(), indexed.path.clone());
+
+            links
+                .links
+                .extend(indexed.links.iter().cloned().map(|link| SourcedLink {
+                    source: note.clone(),
+                    key: normalized_name(&link.name),
+                    link,
+                }));
+            links
+                .by_key
+                .entry(note.key.clone())
+                .or_insert(links.notes.len());
+            links.notes.push(note);
+        }
+
+        links
+    }
+
+    /// The note a link name points to, if it exists.
+    pub fn resolve(&self, name: &str) -> Option<&NoteFile> {
+        self.resolve_key(&normalized_name(name))
+    }
+
+    fn resolve_key(&self, key: &str) -> Option<&NoteFile> {
+        self.by_key.get(key).map(|index| &self.notes[*index])
+    }
+
+    /// The links written in `note`.
+    pub fn outgoing(&self, note: &NoteFile) -> Vec<&SourcedLink> {
+        self.links
+            .iter()
+            .filter(|link| link.source.path == note.path)
+            .collect()
+    }
+
+    /// The links in other notes that point to any of `names`.
+    pub fn backlinks(&self, names: &[&str]) -> Vec<&SourcedLink> {
+        let keys: Vec<String> = names.iter().map(|name| normalized_name(name)).collect();
+
+        self.links
+            .iter()
+            .filter(|link| keys.contains(&link.key))
+            .filter(|link| !keys.contains(&link.source.key))
+            .collect()
+    }
+
+    /// The links whose target doesn't match any indexed note.
+    pub fn dangling(&self) -> Vec<&SourcedLink> {
+        self.links
+            .iter()
+            .filter(|link| self.resolve_key(&link.key).is_none())
+            .collect()
+    }
+}
+
+pub fn note_file(path: &Path) -> Option<NoteFile> {
+    Some(NoteFile::new(
+        path.file_stem()?.to_string_lossy().into_owned(),
+        path.to_string_lossy().into_owned(),
+    ))
+}
+
+const WIKI_LINK: &str = r"\[\[([^\[\]|#]+)(?:#[^\[\]|]*)?(?:\|[^\[\]]*)?\]\]";
+const MARKDOWN_LINK: &str = r"\[[^\[\]]*\]\(([^()\s#]+)(?:#[^()\s]*)?\)";
+
+/// The link regexes, compiled once since every indexed note goes through them.
+fn link_regexes() -> &'static (Regex, Regex) {
+    static REGEXES: OnceLock<(Regex, Regex)> = OnceLock::new();
+    REGEXES.get_or_init(|| {
+        (
+            Regex::new(WIKI_LINK).unwrap(),
+            Regex::new(MARKDOWN_LINK).unwrap(),
+        )
+    })
+}
+
+/// The wiki-links and links to local Markdown files in `contents`, ignoring fenced code blocks.
+pub fn extract_links(contents: &str) -> Vec<Link> {
+    let (wiki, markdown) = link_regexes();
+
+    let mut links = Vec::new();
+    let mut in_code_block = false;
+
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        for captures in wiki.captures_iter(line) {
+            let target = captures[1].trim().to_string();
+            links.push(Link {
+                kind: LinkKind::Wiki,
+                name: link_name(&target),
+                target,
+                line: index + 1,
+            });
+        }
+
+        for captures in markdown.captures_iter(line) {
+            let target = &captures[1];
+            if !is_local_markdown_target(target) {
+                continue;
+            }
+
+            links.push(Link {
+                kind: LinkKind::Markdown,
+                name: link_name(&target.replace("%20", " ")),
+                target: target.to_string(),
+                line: index + 1,
+            });
+        }
+    }
+
+    links
+}
+
+/// Replaces the target of every link for which `replace` returns a new one, keeping aliases,
+/// headings and everything outside the links as written. Fenced code blocks are left alone.
+pub fn rewrite_links(contents: &str, mut replace: impl FnMut(&Link) -> Option<String>) -> String {
+    let (wiki, markdown) = link_regexes();
+
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+        }
+        if in_code_block || line.trim_start().starts_with("```") {
+            lines.push(line.to_owned());
+            continue;
+        }
+
+        let mut replacements = Vec::new();
+        for captures in wiki.captures_iter(line) {
+            let span = captures.get(1).unwrap();
+            let target = span.as_str().trim().to_string();
+            let link = Link {
+                kind: LinkKind::Wiki,
+                name: link_name(&target),
+                target,
+                line: index + 1,
+            };
+            if let Some(new_target) = replace(&link) {
+                replacements.push((span.range(), new_target));
+            }
+        }
+        for captures in markdown.captures_iter(line) {
+            let span = captures.get(1).unwrap();
+            if !is_local_markdown_target(span.as_str()) {
+                continue;
+            }
+            let link = Link {
+                kind: LinkKind::Markdown,
+                name: link_name(&span.as_str().replace("%20", " ")),
+                target: span.as_str().to_string(),
+                line: index + 1,
+            };
+            if let Some(new_target) = replace(&link) {
+                replacements.push((span.range(), new_target));
+            }
+        }
+
+        let mut line = line.to_owned();
+        replacements.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, new_target) in replacements {
+            line.replace_range(range, &new_target);
+        }
+        lines.push(line);
+    }
+
+    let mut rewritten = lines.join("\n");
+    if contents.ends_with('\n') {
+        rewritten.push('\n');
+    }
+    rewritten
+}
+
+fn is_local_markdown_target(target: &str) -> bool {
+    !target.is_empty() && !target.contains("://") && target.ends_with(".md")
+}
+
+fn link_name(target: &str) -> String {
+    let file_name = target.rsplit('/').next().unwrap_or(target);
+    file_name
+        .strip_suffix(".md")
+        .unwrap_or(file_name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::markdown_files;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn extracts_wiki_and_markdown_links() {
+        let contents = "See [[Ada Lovelace|Ada]] and [[2026-04-19#Log]].\n\
+                        Also [notes](../notes/ada%20lovelace.md#bio) and [site](https://x.io/a.md).\n\
+                        ```\n[[Not A Link]]\n```\n";
+
+        let links = extract_links(contents);
+        let names: Vec<(&str, &str, usize)> = links
+            .iter()
+            .map(|link| (link.target.as_str(), link.name.as_str(), link.line))
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                ("Ada Lovelace", "Ada Lovelace", 1),
+                ("2026-04-19", "2026-04-19", 1),
+                ("../notes/ada%20lovelace.md", "ada lovelace", 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn rewrites_link_targets_and_keeps_the_rest() {
+        let contents =
+            "See [[Ada Lovelace|Ada]], [[ada lovelace#Bio]] and [x](notes/ada-lovelace.md#bio).\n\
+                        ```\n[[Ada Lovelace]]\n```\n[[Babbage]]\n";
+
+        let rewritten = rewrite_links(contents, |link| {
+            (normalized_name(&link.name) == "adalovelace").then(|| match link.kind {
+                LinkKind::Wiki => String::from("Augusta King"),
+                LinkKind::Markdown => String::from("notes/augusta-king.md"),
+            })
+        });
+
+        assert_eq!(
+            rewritten,
+            "See [[Augusta King|Ada]], [[Augusta King#Bio]] and [x](notes/augusta-king.md#bio).\n\
+             ```\n[[Ada Lovelace]]\n```\n[[Babbage]]\n"
+        );
+    }
+
+    #[test]
+    fn finds_backlinks_across_folders_regardless_of_case_style() {
+        let notes = TempDir::new().unwrap();
+        let journal = TempDir::new().unwrap();
+        fs::write(notes.path().join("ada-lovelace.md"), "[[Charles Babbage]]").unwrap();
+        fs::write(notes.path().join("computing.md"), "[[Ada Lovelace]]").unwrap();
+        fs::write(journal.path().join("2026-04-19.md"), "[x](ada-lovelace.md)").unwrap();
+
+        let mut note_index = NoteIndex::default();
+        note_index
+            .refresh(&[markdown_files(notes.path()), markdown_files(journal.path())].concat());
+
+        let index = LinkIndex::from_index(&note_index);
+        let mut sources: Vec<&str> = index
+            .backlinks(&["Ada Lovelace"])
+            .iter()
+            .map(|link| link.source.name.as_str())
+            .collect();
+        sources.sort();
+
+        assert_eq!(sources, vec!["2026-04-19", "computing"]);
+        assert_eq!(
+            index
+                .dangling()
+                .iter()
+                .map(|link| link.link.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Charles Babbage"]
+        );
+    }
+}
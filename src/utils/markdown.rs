@@ -0,0 +1,583 @@
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// Where `zn insert` places a rendered template inside an existing note.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InsertPosition {
+    AfterHeading(String),
+    AtMarker(String),
+    Top,
+    Bottom,
+}
+
+/// The level of an ATX heading such as `## Log`, or `None` for any other line.
+pub fn heading_level(line: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    let level = trimmed.chars().take_while(|char| *char == '#').count();
+
+    if (1..=6).contains(&level) && (trimmed.len() == level || trimmed[level..].starts_with(' ')) {
+        Some(level)
+    } else {
+        None
+    }
+}
+
+/// Whether `line` is the heading `heading`, which may omit its `#` markers to match any level.
+pub fn is_heading(line: &str, heading: &str) -> bool {
+    if heading_level(line).is_none() {
+        return false;
+    }
+
+    if heading.trim_start().starts_with('#') {
+        line.trim() == heading.trim()
+    } else {
+        line.trim().trim_start_matches('#').trim() == heading.trim()
+    }
+}
+
+/// The line index of the heading and the exclusive end of its section, which runs until the
+/// next heading of the same or a higher level.
+pub fn find_section(lines: &[&str], heading: &str) -> Option<(usize, usize)> {
+    let start = lines.iter().position(|line| is_heading(line, heading))?;
+    let level = heading_level(lines[start])?;
+
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| heading_level(line).is_some_and(|other| other <= level))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    Some((start, end))
+}
+
+/// The body of a section without its heading and surrounding blank lines, or the whole
+/// document below any front matter when `heading` is `None`.
+pub fn section_body(contents: &str, heading: Option<&str>) -> Option<String> {
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let body = match heading {
+        Some(heading) => {
+            let (start, end) = find_section(&lines, heading)?;
+            &lines[start + 1..end]
+        }
+        None => &lines[front_matter_end(&lines)..],
+    };
+
+    Some(body.join("\n").trim_matches('\n').to_string())
+}
+
+/// Appends `block` after the last non-blank line of the section, creating the section at the
+/// end of the document when it doesn't exist yet.
+pub fn insert_into_section(contents: &str, heading: &str, block: &str) -> String {
+    let mut lines: Vec<&str> = contents.lines().collect();
+
+    let Some((start, end)) = find_section(&lines, heading) else {
+        let section = format!("{}\n\n{}", heading.trim(), block.trim_end_matches('\n'));
+        return append_block(contents, &section);
+    };
+
+    // An empty section keeps the blank line that usually follows its heading.
+    let first_body_line = match lines.get(start + 1) {
+        Some(line) if start + 1 < end && line.trim().is_empty() => start + 2,
+        _ => start + 1,
+    };
+    let last_content_line = (start + 1..end)
+        .rev()
+        .find(|index| !lines[*index].trim().is_empty());
+    let insert_at = last_content_line.map_or(first_body_line, |index| index + 1);
+
+    let mut block_lines: Vec<&str> = block.trim_end_matches('\n').lines().collect();
+    if last_content_line.is_none() && insert_at == end && end < lines.len() {
+        block_lines.push("");
+    }
+
+    lines.splice(insert_at..insert_at, block_lines);
+    join_lines(&lines)
+}
+
+/// Appends `block` to the end of the document on a line of its own.
+pub fn append_block(contents: &str, block: &str) -> String {
+    let mut updated = contents.to_string();
+
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+
+    updated.push_str(block.trim_end_matches('\n'));
+    updated.push('\n');
+    updated
+}
+
+/// Inserts `block` at `position`, separated from the surrounding text by blank lines.
+pub fn insert_block(
+    contents: &str,
+    position: &InsertPosition,
+    block: &str,
+) -> Result<String, String> {
+    let block = block.trim_matches('\n');
+    let mut lines: Vec<&str> = contents.lines().collect();
+
+    let insert_at = match position {
+        InsertPosition::Bottom => {
+            let separator = if contents.trim().is_empty() { "" } else { "\n" };
+            return Ok(append_block(contents, &format!("{separator}{block}")));
+        }
+        InsertPosition::Top => front_matter_end(&lines),
+        InsertPosition::AfterHeading(heading) => {
+            let (start, _) = find_section(&lines, heading)
+                .ok_or_else(|| format!("heading '{heading}' not found"))?;
+            // Skip the blank line below the heading so the block keeps it.
+            match lines.get(start + 1) {
+                Some(line) if line.trim().is_empty() => start + 2,
+                _ => start + 1,
+            }
+        }
+        InsertPosition::AtMarker(marker) => lines
+            .iter()
+            .position(|line| line.contains(marker.as_str()))
+            .ok_or_else(|| format!("marker '{marker}' not found"))?,
+    };
+
+    let mut block_lines: Vec<&str> = block.lines().collect();
+    if insert_at > 0 && !lines[insert_at - 1].trim().is_empty() {
+        block_lines.insert(0, "");
+    }
+    if lines
+        .get(insert_at)
+        .is_some_and(|line| !line.trim().is_empty())
+    {
+        block_lines.push("");
+    }
+
+    lines.splice(insert_at..insert_at, block_lines);
+    Ok(join_lines(&lines))
+}
+
+/// The `key: value` (YAML) or `key = "value"` (TOML) pairs of a leading front matter block,
+/// with quotes removed from the values. A YAML list written one `- item` per line is read as
+/// `[item, ...]`, like a list written inline.
+pub fn front_matter_entries(contents: &str) -> Vec<(String, String)> {
+    let lines: Vec<&str> = contents.lines().collect();
+    let end = front_matter_end(&lines);
+    let block = lines.get(1..end.saturating_sub(1)).unwrap_or_default();
+
+    let mut entries = Vec::new();
+    for (index, line) in block.iter().enumerate() {
+        if line.starts_with(char::is_whitespace) || line.starts_with('-') {
+            continue;
+        }
+        let Some(separator) = line.find([':', '=']) else {
+            continue;
+        };
+
+        let name = line[..separator].trim().to_string();
+        let mut value = line[separator + 1..]
+            .trim()
+            .trim_matches(['"', '\''])
+            .to_string();
+        if value.is_empty() {
+            let items: Vec<&str> = block[index + 1..]
+                .iter()
+                .map_while(|line| line.trim_start().strip_prefix("- "))
+                .map(str::trim)
+                .collect();
+            if !items.is_empty() {
+                value = format!("[{}]", items.join(", "));
+            }
+        }
+
+        entries.push((name, value));
+    }
+
+    entries
+}
+
+/// The items of a front matter value written as `[a, "b"]` or as `a, b`.
+pub fn front_matter_list(value: &str) -> Vec<String> {
+    let value = value.trim();
+    let value = value
+        .strip_prefix('[')
+        .and_then(|value| value.strip_suffix(']'))
+        .unwrap_or(value);
+
+    value
+        .split(',')
+        .map(|item| item.trim().trim_matches(['"', '\'']).to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+/// The text of every heading outside fenced code blocks.
+pub fn headings(contents: &str) -> Vec<String> {
+    outside_code_blocks(contents)
+        .filter_map(|line| {
+            let level = heading_level(line)?;
+            Some(line.trim_start()[level..].trim().to_string())
+        })
+        .collect()
+}
+
+/// The tags of the front matter followed by the inline `#tags`, without duplicates. Tags that
+/// only differ in case are the same tag, spelled the way it first appears.
+pub fn note_tags(contents: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in front_matter_tags(contents)
+        .into_iter()
+        .chain(inline_tags(contents))
+    {
+        if !tags
+            .iter()
+            .any(|existing| existing.to_lowercase() == tag.to_lowercase())
+        {
+            tags.push(tag);
+        }
+    }
+
+    tags
+}
+
+/// The `tags` of the front matter, written as a list (`[a, b]` or one `- a` per line) or as
+/// comma-separated words.
+pub fn front_matter_tags(contents: &str) -> Vec<String> {
+    let value = front_matter_entries(contents)
+        .into_iter()
+        .find_map(|(name, value)| (name == "tags").then_some(value))
+        .unwrap_or_default();
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in front_matter_list(&value) {
+        let tag = tag.trim_start_matches('#');
+        if !tag.is_empty() && !tags.iter().any(|existing| existing == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+
+    tags
+}
+
+/// The inline `#tags` outside front matter, headings and fenced code blocks, without the `#`.
+pub fn inline_tags(contents: &str) -> Vec<String> {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = REGEX
+        .get_or_init(|| Regex::new(r"(?:^|\s)#([\p{L}\p{N}_/-]*\p{L}[\p{L}\p{N}_/-]*)").unwrap());
+    let mut tags: Vec<String> = Vec::new();
+
+    let lines: Vec<&str> = contents.lines().collect();
+    let body = lines[front_matter_end(&lines)..].join("\n");
+
+    for line in outside_code_blocks(&body).filter(|line| heading_level(line).is_none()) {
+        for captures in regex.captures_iter(line) {
+            let tag = captures[1].to_string();
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+
+    tags
+}
+
+/// The lines of `contents` that aren't part of a fenced code block or its fences.
+pub fn outside_code_blocks(contents: &str) -> impl Iterator<Item = &str> {
+    let mut in_code_block = false;
+
+    contents.lines().filter(move |line| {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            return false;
+        }
+        !in_code_block
+    })
+}
+
+/// The index of the first line after a leading `---` or `+++` front matter block.
+pub fn front_matter_end(lines: &[&str]) -> usize {
+    let Some(delimiter) = lines
+        .first()
+        .map(|line| line.trim_end())
+        .filter(|line| *line == "---" || *line == "+++")
+    else {
+        return 0;
+    };
+
+    lines[1..]
+        .iter()
+        .position(|line| line.trim_end() == delimiter)
+        .map_or(0, |offset| offset + 2)
+}
+
+/// The unchecked `- [ ]` items of a section together with their nested lines, dedented so the
+/// items sit at the left margin.
+pub fn unchecked_tasks(contents: &str, heading: &str) -> Vec<String> {
+    let lines: Vec<&str> = contents.lines().collect();
+
+    unchecked_task_blocks(&lines, heading)
+        .into_iter()
+        .flat_map(|(start, end)| {
+            let indent = indentation(lines[start]);
+            lines[start..end]
+                .iter()
+                .map(move |line| line.get(indent..).unwrap_or(line.trim_start()).to_string())
+        })
+        .collect()
+}
+
+/// Marks the unchecked items of a section with `[>]`, the bullet journal sign for migrated tasks.
+pub fn mark_tasks_migrated(contents: &str, heading: &str) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let starts: Vec<usize> = unchecked_task_blocks(&lines, heading)
+        .into_iter()
+        .map(|(start, _)| start)
+        .collect();
+
+    let migrated: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            if starts.contains(&index) {
+                line.replacen("[ ]", "[>]", 1)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+
+    join_lines(&migrated.iter().map(String::as_str).collect::<Vec<_>>())
+}
+
+fn unchecked_task_blocks(lines: &[&str], heading: &str) -> Vec<(usize, usize)> {
+    let Some((start, end)) = find_section(lines, heading) else {
+        return vec![];
+    };
+
+    let mut blocks = Vec::new();
+    let mut index = start + 1;
+    while index < end {
+        if !is_unchecked_task(lines[index]) {
+            index += 1;
+            continue;
+        }
+
+        let indent = indentation(lines[index]);
+        let block_end = (index + 1..end)
+            .find(|next| lines[*next].trim().is_empty() || indentation(lines[*next]) <= indent)
+            .unwrap_or(end);
+
+        blocks.push((index, block_end));
+        index = block_end;
+    }
+
+    blocks
+}
+
+fn is_unchecked_task(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    ["- [ ] ", "* [ ] ", "+ [ ] "]
+        .iter()
+        .any(|marker| trimmed.starts_with(marker) || trimmed == marker.trim_end())
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+fn join_lines(lines: &[&str]) -> String {
+    let mut joined = lines.join("\n");
+    joined.push('\n');
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_heading_levels() {
+        assert_eq!(heading_level("## Log"), Some(2));
+        assert_eq!(heading_level("#"), Some(1));
+        assert_eq!(heading_level("#tag"), None);
+        assert_eq!(heading_level("####### too deep"), None);
+    }
+
+    #[test]
+    fn inserts_at_end_of_section_before_next_heading() {
+        let contents = "# Day\n\n## Log\n\n- 08:00 first\n\n## Notes\n\ntext\n";
+        let updated = insert_into_section(contents, "## Log", "- 09:00 second");
+
+        assert_eq!(
+            updated,
+            "# Day\n\n## Log\n\n- 08:00 first\n- 09:00 second\n\n## Notes\n\ntext\n"
+        );
+    }
+
+    #[test]
+    fn keeps_blank_line_below_heading_of_empty_section() {
+        let updated = insert_into_section("## Log\n\n## Notes\n", "## Log", "- entry");
+
+        assert_eq!(updated, "## Log\n\n- entry\n\n## Notes\n");
+    }
+
+    #[test]
+    fn matches_headings_without_markers() {
+        let contents = "## Log\n### Morning\n- a\n## Notes\n";
+        let updated = insert_into_section(contents, "Log", "- b");
+
+        assert_eq!(updated, "## Log\n### Morning\n- a\n- b\n## Notes\n");
+    }
+
+    #[test]
+    fn creates_missing_section_at_end() {
+        let updated = insert_into_section("# Day", "## Log", "- entry");
+
+        assert_eq!(updated, "# Day\n## Log\n\n- entry\n");
+    }
+
+    #[test]
+    fn inserts_block_below_heading() {
+        let contents = "# Project\n\n## Meetings\n\n### Older\n";
+        let position = InsertPosition::AfterHeading("## Meetings".to_string());
+
+        assert_eq!(
+            insert_block(contents, &position, "### New\n").unwrap(),
+            "# Project\n\n## Meetings\n\n### New\n\n### Older\n"
+        );
+    }
+
+    #[test]
+    fn inserts_block_before_marker_and_keeps_it() {
+        let contents = "# Project\n<!-- zn:insert -->\n";
+        let position = InsertPosition::AtMarker("<!-- zn:insert -->".to_string());
+
+        assert_eq!(
+            insert_block(contents, &position, "block").unwrap(),
+            "# Project\n\nblock\n\n<!-- zn:insert -->\n"
+        );
+    }
+
+    #[test]
+    fn inserts_block_at_top_after_front_matter() {
+        let contents = "---\ntitle: X\n---\n# X\n";
+
+        assert_eq!(
+            insert_block(contents, &InsertPosition::Top, "block").unwrap(),
+            "---\ntitle: X\n---\n\nblock\n\n# X\n"
+        );
+        assert_eq!(
+            insert_block("# X\n", &InsertPosition::Bottom, "block").unwrap(),
+            "# X\n\nblock\n"
+        );
+    }
+
+    #[test]
+    fn reports_missing_marker() {
+        let position = InsertPosition::AtMarker("<!-- zn -->".to_string());
+
+        assert_eq!(
+            insert_block("text", &position, "block").unwrap_err(),
+            "marker '<!-- zn -->' not found"
+        );
+    }
+
+    const TASKS: &str = "## Tasks\n- [x] done\n- [ ] open\n  - [x] sub step\n  notes\n- [>] moved\n  - [ ] nested open\n## Notes\n- [ ] elsewhere\n";
+
+    #[test]
+    fn extracts_unchecked_tasks_with_their_children() {
+        assert_eq!(
+            unchecked_tasks(TASKS, "Tasks"),
+            vec![
+                "- [ ] open",
+                "  - [x] sub step",
+                "  notes",
+                "- [ ] nested open"
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_unchecked_tasks_as_migrated() {
+        assert_eq!(
+            mark_tasks_migrated(TASKS, "Tasks"),
+            "## Tasks\n- [x] done\n- [>] open\n  - [x] sub step\n  notes\n- [>] moved\n  - [>] nested open\n## Notes\n- [ ] elsewhere\n"
+        );
+    }
+
+    #[test]
+    fn extracts_section_body_including_subsections() {
+        let contents = "# Day\n## Highlights\n\n- one\n### More\n- two\n\n## Notes\n";
+
+        assert_eq!(
+            section_body(contents, Some("Highlights")).as_deref(),
+            Some("- one\n### More\n- two")
+        );
+        assert_eq!(section_body(contents, Some("Missing")), None);
+        assert_eq!(
+            section_body("---\na: 1\n---\n\nbody\n", None).as_deref(),
+            Some("body")
+        );
+    }
+
+    #[test]
+    fn reads_front_matter_entries() {
+        let entry = vec![("template".to_string(), "meeting".to_string())];
+
+        assert_eq!(front_matter_entries("---\ntemplate: meeting\n---\n"), entry);
+        assert_eq!(
+            front_matter_entries("+++\ntemplate = \"meeting\"\n+++\n"),
+            entry
+        );
+        assert!(front_matter_entries("template: meeting\n").is_empty());
+        assert_eq!(
+            front_matter_entries("+++\ntitle = \"Q2: plan\"\naliases = [\"Plan\"]\n+++\n"),
+            vec![
+                ("title".to_string(), "Q2: plan".to_string()),
+                ("aliases".to_string(), "[\"Plan\"]".to_string())
+            ]
+        );
+        assert_eq!(
+            front_matter_entries("---\naliases:\n  - Plan\n  - Q2 plan\n---\n"),
+            vec![("aliases".to_string(), "[Plan, Q2 plan]".to_string())]
+        );
+        assert_eq!(
+            front_matter_list("[Plan, \"Q2 plan\"]"),
+            vec!["Plan", "Q2 plan"]
+        );
+    }
+
+    #[test]
+    fn extracts_headings_and_inline_tags() {
+        let contents = "# Title #notatag\nSome #idea and #work/project, not#this or #2026.\n```\n#code\n```\n## Next\n";
+
+        assert_eq!(headings(contents), vec!["Title #notatag", "Next"]);
+        assert_eq!(inline_tags(contents), vec!["idea", "work/project"]);
+    }
+
+    #[test]
+    fn reads_front_matter_tags_in_every_list_style() {
+        assert_eq!(
+            front_matter_tags("---\ntags: [meeting, \"work/q2\"]\n---\n"),
+            vec!["meeting", "work/q2"]
+        );
+        assert_eq!(
+            front_matter_tags("---\ntags:\n  - meeting\n  - '#work'\ntitle: x\n---\n"),
+            vec!["meeting", "work"]
+        );
+        assert_eq!(
+            front_matter_tags("+++\ntags = [\"meeting\"]\n+++\n"),
+            vec!["meeting"]
+        );
+        assert_eq!(
+            note_tags("---\ntags: meeting, idea\n---\nAn #idea and #followup\n"),
+            vec!["meeting", "idea", "followup"]
+        );
+        assert_eq!(
+            note_tags("---\ntags: [Work, work]\n---\n#WORK and #work and #Idea\n"),
+            vec!["Work", "Idea"]
+        );
+    }
+
+    #[test]
+    fn appends_block_on_its_own_line() {
+        assert_eq!(append_block("text", "- entry"), "text\n- entry\n");
+        assert_eq!(append_block("", "- entry"), "- entry\n");
+    }
+}
@@ -3,10 +3,23 @@ use directories::BaseDirs;
 use nix::unistd::execvp;
 use std::{env, ffi::CString, fs, io, path::Path, path::PathBuf, process};
 
+pub mod capture;
+pub mod carryover;
 pub mod casing;
+pub mod clock;
 pub mod date;
+pub mod embed;
+pub mod frontmatter;
+pub mod fuzzy;
+pub mod holidays;
+pub mod index;
+pub mod links;
+pub mod markdown;
 pub mod placeholder;
+pub mod rename;
 pub mod template;
+pub mod trash;
+pub mod vault;
 
 pub fn command_folder_path(command: Sub) -> Result<String, Box<dyn std::error::Error>> {
     let config = Config::read()?;
@@ -36,16 +49,29 @@ pub fn check_note_name(
 
             fs::create_dir_all(&path)?;
 
-            let dir_contents: Vec<String> = fs::read_dir(&path)?
+            let mut dir_contents: Vec<String> = fs::read_dir(&path)?
                 .filter_map(|entry| entry.ok())
                 .filter_map(|entry| entry.file_name().into_string().ok())
                 .filter(|name| name.ends_with(".md"))
                 .map(|name| name.trim_end_matches(".md").to_string())
                 .collect();
-
-            if dir_contents.contains(&name.to_owned()) {
+            dir_contents.sort();
+
+            // An exact match wins, otherwise names that only differ in case style or accents,
+            // such as `My Note` and `my-note`, count as the same note.
+            let normalized = casing::normalized_name(name);
+            let existing = dir_contents
+                .iter()
+                .find(|existing| *existing == name)
+                .or_else(|| {
+                    dir_contents
+                        .iter()
+                        .find(|existing| casing::normalized_name(existing) == normalized)
+                });
+
+            if let Some(existing) = existing {
                 let full_path = PathBuf::from(path)
-                    .join(format!("{name}.md"))
+                    .join(format!("{existing}.md"))
                     .to_string_lossy()
                     .into_owned();
 
@@ -58,6 +84,55 @@ pub fn check_note_name(
     Ok(None)
 }
 
+/// Resolves a note given either as a path, also relative to the note folder, or by name, looking
+/// names up like `check_note_name`.
+pub fn resolve_note_path(input: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let path = alternate_path(input.to_owned());
+    if Path::new(&path).is_file() {
+        return Ok(path);
+    }
+
+    // A path relative to the note folder, such as `projects/apollo`.
+    let in_note_folder = PathBuf::from(command_folder_path(Sub::Note)?)
+        .join(format!("{}.md", path.trim_end_matches(".md")));
+    if Path::new(&path).is_relative() && in_note_folder.is_file() {
+        return Ok(in_note_folder.to_string_lossy().into_owned());
+    }
+
+    let name = input.trim_end_matches(".md");
+    for candidate in [casing::convert_case(name.to_owned()), name.to_owned()] {
+        if let Some(existing_path) = check_note_name(&candidate, Sub::Note)? {
+            return Ok(existing_path);
+        }
+    }
+
+    Err(io::Error::other(format!("note '{input}' not found")).into())
+}
+
+/// Every Markdown file below `dir`, skipping hidden files and folders.
+pub fn markdown_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut files = Vec::new();
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+
+        if path.is_dir() {
+            files.extend(markdown_files(&path));
+        } else if path.extension().is_some_and(|extension| extension == "md") {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    files
+}
+
 pub fn check_journal_note_path(full_path: &str) -> Option<String> {
     if Path::new(full_path).is_file() {
         return Some(full_path.to_string());
@@ -66,7 +141,41 @@ pub fn check_journal_note_path(full_path: &str) -> Option<String> {
     None
 }
 
+/// Copies `path` to `path.bak` before it is edited in place, returning the backup's path.
+pub fn backup_file(path: &str) -> io::Result<String> {
+    let backup_path = format!("{path}.bak");
+    fs::copy(path, &backup_path)?;
+    Ok(backup_path)
+}
+
+/// Writes through a temporary sibling file and renames it over `path`, so readers never see a
+/// partially written note.
+pub fn write_atomically(path: &str, contents: &str) -> io::Result<()> {
+    let path = Path::new(path);
+    let temp_path = temp_path(path)?;
+
+    fs::write(&temp_path, contents)?;
+    fs::rename(&temp_path, path).map_err(|err| {
+        let _ = fs::remove_file(&temp_path);
+        err
+    })
+}
+
+/// The hidden sibling a new version of `path` is written to before it replaces the file.
+pub(crate) fn temp_path(path: &Path) -> io::Result<PathBuf> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::other(format!("invalid note path: {}", path.display())))?;
+
+    Ok(path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy())))
+}
+
 pub fn open_path_in_editor(path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    open_paths_in_editor(&[path.to_owned()])
+}
+
+/// Replaces the current process with the editor, passing every path as an argument.
+pub fn open_paths_in_editor(paths: &[String]) -> Result<(), Box<dyn std::error::Error>> {
     let no_editor = env::var("ZAPNOTE_NO_EDITOR")?;
     let parsed_no_editor: bool = no_editor.parse().unwrap_or(false);
 
@@ -80,20 +189,24 @@ pub fn open_path_in_editor(path: &str) -> Result<(), Box<dyn std::error::Error>>
     match default_editor.as_deref() {
         Some("") | None => {
             let editor = env::var("EDITOR").unwrap_or("vi".to_string());
-            run_editor(&editor, path);
+            run_editor(&editor, paths);
         }
         Some(editor) => {
-            run_editor(editor, path);
+            run_editor(editor, paths);
         }
     }
 
     Ok(())
 }
 
-fn run_editor(editor: &str, path: &str) {
+fn run_editor(editor: &str, paths: &[String]) {
     let editor_cstr = CString::new(editor).expect("CString::new failed editor");
-    let path_cstr = CString::new(path).expect("CString::new failed path");
-    let args = [editor_cstr.clone(), path_cstr];
+    let mut args = vec![editor_cstr.clone()];
+    args.extend(
+        paths
+            .iter()
+            .map(|path| CString::new(path.as_str()).expect("CString::new failed path")),
+    );
 
     let Err(err) = execvp(&editor_cstr, &args);
     let error = io::Error::other(format!("error executing {editor}: {err}"));
@@ -101,7 +214,7 @@ fn run_editor(editor: &str, path: &str) {
     process::exit(1);
 }
 
-fn alternate_path(path: String) -> String {
+pub(crate) fn alternate_path(path: String) -> String {
     if path.starts_with("~/") {
         if let Some(base_dirs) = BaseDirs::new() {
             let home_dir = base_dirs.home_dir().to_str().unwrap();
@@ -165,6 +278,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn write_atomically_replaces_contents_without_leftovers() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let file_path = temp_dir.path().join("inbox.md");
+        std::fs::write(&file_path, "old").unwrap();
+
+        write_atomically(file_path.to_str().unwrap(), "new").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "new");
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn markdown_files_walks_folders_and_skips_hidden_entries() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("2026")).unwrap();
+        std::fs::create_dir_all(root.join(".trash")).unwrap();
+        ["a.md", "2026/b.md", ".trash/c.md", ".hidden.md", "d.txt"]
+            .iter()
+            .for_each(|file| std::fs::write(root.join(file), "").unwrap());
+
+        assert_eq!(
+            markdown_files(root),
+            vec![root.join("2026/b.md"), root.join("a.md")]
+        );
+    }
+
     #[test]
     fn check_journal_note_path_detects_existing_file() {
         let temp_dir = tempfile::TempDir::new().unwrap();
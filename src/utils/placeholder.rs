@@ -1,20 +1,106 @@
-use chrono::{DateTime, Local, NaiveDate};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, SecondsFormat};
 use regex::Regex;
 
-use crate::utils::date::{apply_date_offset, format_date, format_datetime, parse_date_offset};
+use crate::{
+    config::{JournalConfig, JournalPeriod},
+    utils::{
+        carryover::CarryOver,
+        clock::parse_timezone,
+        date::{
+            format_date, format_datetime, parse_date_range, period_end, period_start,
+            resolve_relative_date, Calendar,
+        },
+        embed::{embed_journal, embed_note},
+        links::LinkIndex,
+    },
+};
 
 pub struct TemplateContext {
     pub title: String,
-    pub now: DateTime<Local>,
+    pub now: DateTime<FixedOffset>,
     pub reference_date: NaiveDate,
+    pub period: JournalPeriod,
+    pub calendar: Calendar,
+    pub text: Option<String>,
+    pub journal: Option<JournalConfig>,
+    pub tags: Vec<String>,
+    pub template: Option<String>,
+    pub aliases: Vec<String>,
+    pub carryover: Option<CarryOver>,
 }
 
 impl TemplateContext {
     pub fn new(title: String, reference_date: NaiveDate) -> Self {
         Self {
             title,
-            now: Local::now(),
+            now: Local::now().fixed_offset(),
             reference_date,
+            period: JournalPeriod::Day,
+            calendar: Calendar::default(),
+            text: None,
+            journal: None,
+            tags: Vec::new(),
+            template: None,
+            aliases: Vec::new(),
+            carryover: None,
+        }
+    }
+
+    pub fn with_period(mut self, period: JournalPeriod) -> Self {
+        self.period = period;
+        self
+    }
+
+    pub fn with_now(mut self, now: DateTime<FixedOffset>) -> Self {
+        self.now = now;
+        self
+    }
+
+    pub fn with_calendar(mut self, calendar: Calendar) -> Self {
+        self.calendar = calendar;
+        self
+    }
+
+    pub fn with_text(mut self, text: String) -> Self {
+        self.text = Some(text);
+        self
+    }
+
+    pub fn with_journal(mut self, journal: JournalConfig) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_template(mut self, template: String) -> Self {
+        self.template = Some(template);
+        self
+    }
+
+    pub fn with_aliases(mut self, aliases: Vec<String>) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    pub fn with_carryover(mut self, carryover: CarryOver) -> Self {
+        self.carryover = Some(carryover);
+        self
+    }
+
+    fn date_value(&self, name: &str) -> Option<NaiveDate> {
+        match name {
+            "date" => Some(self.reference_date),
+            "period_start" => Some(period_start(
+                self.reference_date,
+                self.period,
+                &self.calendar,
+            )),
+            "period_end" => Some(period_end(self.reference_date, self.period, &self.calendar)),
+            _ => None,
         }
     }
 }
@@ -22,58 +108,114 @@ impl TemplateContext {
 pub struct Placeholder;
 
 impl Placeholder {
-    pub fn parse(template: String, context: &TemplateContext) -> String {
+    /// Renders every known placeholder, leaving unknown ones untouched.
+    pub fn parse(template: String, context: &TemplateContext) -> Result<String, String> {
         let regex = Regex::new(r"\{\{([^{}]+)\}\}").unwrap();
+        let mut rendered = String::with_capacity(template.len());
+        let mut last_end = 0;
+
+        for caps in regex.captures_iter(&template) {
+            let whole = caps.get(0).unwrap();
+            let raw = caps.get(1).unwrap().as_str().trim();
+
+            rendered.push_str(&template[last_end..whole.start()]);
+            match Self::render_placeholder(raw, context)? {
+                Some(value) => rendered.push_str(&value),
+                None => rendered.push_str(whole.as_str()),
+            }
+            last_end = whole.end();
+        }
 
-        regex
-            .replace_all(&template, |caps: &regex::Captures| {
-                let raw = caps.get(1).unwrap().as_str().trim();
-
-                Self::render_placeholder(raw, context)
-                    .unwrap_or_else(|| caps.get(0).unwrap().as_str().to_string())
-            })
-            .to_string()
+        rendered.push_str(&template[last_end..]);
+        Ok(rendered)
     }
 
-    fn render_placeholder(raw: &str, context: &TemplateContext) -> Option<String> {
+    fn render_placeholder(raw: &str, context: &TemplateContext) -> Result<Option<String>, String> {
         if raw == "title" {
-            return Some(context.title.clone());
+            return Ok(Some(context.title.clone()));
+        }
+
+        if raw == "text" {
+            return Ok(context.text.clone());
         }
 
-        if raw == "date" {
-            return Some(format_date(context.reference_date, "%Y-%m-%d"));
+        if let Some(date) = context.date_value(raw) {
+            return format_date(date, "%Y-%m-%d", &context.calendar).map(Some);
         }
 
         if raw == "time" {
-            return Some(format_datetime(context.now, "%H:%M"));
+            return format_datetime(&context.now, "%H:%M", &context.calendar).map(Some);
         }
 
-        if let Some(format) = raw.strip_prefix("date:") {
-            return Some(format_date(context.reference_date, format));
+        if let Some((name, format)) = raw.split_once(':') {
+            if let Some(date) = context.date_value(name) {
+                return format_date(date, format, &context.calendar).map(Some);
+            }
         }
 
         if let Some(format) = raw.strip_prefix("time:") {
-            return Some(format_datetime(context.now, format));
+            return format_datetime(&context.now, format, &context.calendar).map(Some);
         }
 
-        if let Some(attributes) = raw.strip_prefix("date ") {
-            return Self::render_date_with_attributes(attributes, context);
+        if let Some((name, attributes)) = raw.split_once(' ') {
+            if let Some(date) = context.date_value(name) {
+                return Self::render_date_with_attributes(date, attributes, context).map(Some);
+            }
         }
 
         if let Some(attributes) = raw.strip_prefix("time ") {
-            return Self::render_time_with_attributes(attributes, context);
+            return Self::render_time_with_attributes(attributes, context).map(Some);
+        }
+
+        if let Some(attributes) = raw.strip_prefix("carryover") {
+            if attributes.is_empty() || attributes.starts_with(' ') {
+                return Self::render_carryover(attributes, context).map(Some);
+            }
+        }
+
+        if let Some(attributes) = raw.strip_prefix("embed ") {
+            return Self::render_embed(attributes, context).map(Some);
+        }
+
+        if let Some(attributes) = raw.strip_prefix("tags") {
+            if attributes.is_empty() || attributes.starts_with(' ') {
+                return Self::render_tags(attributes, context).map(Some);
+            }
         }
 
-        None
+        if raw == "backlinks" {
+            return Self::render_backlinks(context).map(Some);
+        }
+
+        if raw == "timestamp" {
+            return Ok(Some(context.now.to_rfc3339_opts(SecondsFormat::Secs, true)));
+        }
+
+        if let Some(attributes) = raw.strip_prefix("timestamp ") {
+            let parsed = Self::parse_attributes(attributes);
+            let now = match parsed.get("tz") {
+                Some(tz) => context
+                    .now
+                    .with_timezone(&parse_timezone(tz)?)
+                    .fixed_offset(),
+                None => context.now,
+            };
+
+            return Ok(Some(now.to_rfc3339_opts(SecondsFormat::Secs, true)));
+        }
+
+        Ok(None)
     }
 
-    fn render_date_with_attributes(attributes: &str, context: &TemplateContext) -> Option<String> {
+    fn render_date_with_attributes(
+        mut date: NaiveDate,
+        attributes: &str,
+        context: &TemplateContext,
+    ) -> Result<String, String> {
         let parsed = Self::parse_attributes(attributes);
-        let mut date = context.reference_date;
 
         if let Some(offset) = parsed.get("offset") {
-            let parsed_offset = parse_date_offset(offset).ok()?;
-            date = apply_date_offset(date, parsed_offset);
+            date = resolve_relative_date(date, offset, &context.calendar)?;
         }
 
         let format = parsed
@@ -81,14 +223,127 @@ impl Placeholder {
             .map(String::as_str)
             .unwrap_or("%Y-%m-%d");
 
-        Some(format_date(date, format))
+        format_date(date, format, &context.calendar)
     }
 
-    fn render_time_with_attributes(attributes: &str, context: &TemplateContext) -> Option<String> {
+    fn render_time_with_attributes(
+        attributes: &str,
+        context: &TemplateContext,
+    ) -> Result<String, String> {
         let parsed = Self::parse_attributes(attributes);
         let format = parsed.get("format").map(String::as_str).unwrap_or("%H:%M");
 
-        Some(format_datetime(context.now, format))
+        match parsed.get("tz") {
+            Some(tz) => {
+                let now = context.now.with_timezone(&parse_timezone(tz)?);
+                format_datetime(&now, format, &context.calendar)
+            }
+            None => format_datetime(&context.now, format, &context.calendar),
+        }
+    }
+
+    fn render_carryover(attributes: &str, context: &TemplateContext) -> Result<String, String> {
+        let parsed = Self::parse_attributes(attributes);
+
+        let from = parsed.get("from").map(String::as_str).unwrap_or("previous");
+        if from != "previous" {
+            return Err(format!(
+                "unsupported carryover source '{from}'. the only source is \"previous\""
+            ));
+        }
+
+        let (Some(journal), Some(carryover)) = (&context.journal, &context.carryover) else {
+            return Err("{{carryover}} can only be used in journal templates".to_string());
+        };
+        let section = parsed.get("section").map(String::as_str).unwrap_or("Tasks");
+        let migrate = match parsed.get("migrate") {
+            Some(migrate) => migrate == "true",
+            None => journal.carry_over_migrate.unwrap_or(false),
+        };
+
+        Ok(carryover.take(section, migrate).join("\n"))
+    }
+
+    /// The tags of the note, as a `[a, b]` list for front matter or as inline `#a #b` with
+    /// `style="hashtags"`. `add="a, b"` adds tags every note from the template gets.
+    fn render_tags(attributes: &str, context: &TemplateContext) -> Result<String, String> {
+        let parsed = Self::parse_attributes(attributes);
+
+        let mut tags: Vec<String> = Vec::new();
+        let added = parsed.get("add").map(String::as_str).unwrap_or_default();
+        for tag in added
+            .split(',')
+            .map(str::to_owned)
+            .chain(context.tags.clone())
+        {
+            let tag = tag.trim().trim_start_matches('#');
+            if !tag.is_empty() && !tags.iter().any(|existing| existing == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+
+        match parsed.get("style").map(String::as_str).unwrap_or("list") {
+            "list" => Ok(format!("[{}]", tags.join(", "))),
+            "hashtags" => Ok(tags
+                .iter()
+                .map(|tag| format!("#{tag}"))
+                .collect::<Vec<_>>()
+                .join(" ")),
+            style => Err(format!(
+                "unsupported tags style '{style}'. accepted styles are \"list\" and \"hashtags\""
+            )),
+        }
+    }
+
+    fn render_backlinks(context: &TemplateContext) -> Result<String, String> {
+        let index = LinkIndex::build().map_err(|err| format!("failed to index links: {err}"))?;
+
+        let mut sources: Vec<&str> = index
+            .backlinks(&[&context.title])
+            .into_iter()
+            .map(|link| link.source.name.as_str())
+            .collect();
+        sources.sort();
+        sources.dedup();
+
+        Ok(sources
+            .iter()
+            .map(|source| format!("- [[{source}]]"))
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn render_embed(attributes: &str, context: &TemplateContext) -> Result<String, String> {
+        let parsed = Self::parse_attributes(attributes);
+        let section = parsed.get("section").map(String::as_str);
+
+        if let Some(note) = parsed.get("note") {
+            return embed_note(note, section)
+                .map_err(|err| format!("failed to embed note '{note}': {err}"));
+        }
+
+        let Some(journal) = parsed.get("journal") else {
+            return Err(String::from(
+                "{{embed}} needs a note=\"...\" or journal=\"...\" attribute",
+            ));
+        };
+
+        let (start, end) = match parsed.get("range") {
+            Some(range) => match JournalPeriod::from_name(range) {
+                Some(period) => (
+                    period_start(context.reference_date, period, &context.calendar),
+                    period_end(context.reference_date, period, &context.calendar),
+                ),
+                None => parse_date_range(range, context.reference_date, &context.calendar)?,
+            },
+            None => (
+                period_start(context.reference_date, context.period, &context.calendar),
+                period_end(context.reference_date, context.period, &context.calendar),
+            ),
+        };
+
+        embed_journal(journal, start, end, section, &context.calendar)
+            .map_err(|err| format!("failed to embed journal '{journal}': {err}"))
     }
 
     fn parse_attributes(attributes: &str) -> std::collections::HashMap<String, String> {
@@ -114,7 +369,8 @@ mod tests {
         let rendered = Placeholder::parse(
             "{{title}}".to_string(),
             &context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()),
-        );
+        )
+        .unwrap();
 
         assert_eq!(rendered, "Daily Note");
     }
@@ -124,7 +380,8 @@ mod tests {
         let rendered = Placeholder::parse(
             "{{date:%Y-Q%Q}}".to_string(),
             &context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()),
-        );
+        )
+        .unwrap();
 
         assert_eq!(rendered, "2026-Q2");
     }
@@ -134,7 +391,8 @@ mod tests {
         let rendered = Placeholder::parse(
             "{{date offset=\"-1 day\" format=\"%Y-%m-%d\"}}".to_string(),
             &context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()),
-        );
+        )
+        .unwrap();
 
         assert_eq!(rendered, "2026-04-18");
     }
@@ -144,18 +402,84 @@ mod tests {
         let rendered = Placeholder::parse(
             "{{date format=\"%G-W%V\"}}".to_string(),
             &context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()),
-        );
+        )
+        .unwrap();
 
         assert_eq!(rendered, "2026-W16");
     }
 
+    #[test]
+    fn renders_period_bounds_for_weekly_context() {
+        let context =
+            context(NaiveDate::from_ymd_opt(2026, 4, 13).unwrap()).with_period(JournalPeriod::Week);
+        let rendered = Placeholder::parse(
+            "{{period_start}}..{{period_end format=\"%m-%d\"}}".to_string(),
+            &context,
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "2026-04-13..04-19");
+    }
+
     #[test]
     fn leaves_unknown_placeholders_unchanged() {
         let rendered = Placeholder::parse(
             "{{unknown}}".to_string(),
             &context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()),
-        );
+        )
+        .unwrap();
 
         assert_eq!(rendered, "{{unknown}}");
     }
+
+    #[test]
+    fn renders_custom_tokens_in_date_format() {
+        let rendered = Placeholder::parse(
+            "{{date format=\"%A, %o of %B (H%EH, day %EQ)\"}}".to_string(),
+            &context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()),
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "Sunday, 19th of April (H1, day 19)");
+    }
+
+    #[test]
+    fn reports_unknown_date_tokens() {
+        let error = Placeholder::parse(
+            "{{date format=\"%Y-%K\"}}".to_string(),
+            &context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()),
+        )
+        .unwrap_err();
+
+        assert_eq!(error, "unknown date format token '%K' in '%Y-%K'");
+    }
+
+    #[test]
+    fn renders_time_and_timestamp_in_other_timezones() {
+        let now = DateTime::parse_from_rfc3339("2026-04-19T08:30:00-03:00").unwrap();
+        let context = context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap()).with_now(now);
+        let rendered = Placeholder::parse(
+            "{{time}} {{time tz=\"UTC\"}} {{timestamp}} {{timestamp tz=\"UTC\"}}".to_string(),
+            &context,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            "08:30 11:30 2026-04-19T08:30:00-03:00 2026-04-19T11:30:00Z"
+        );
+    }
+
+    #[test]
+    fn renders_template_and_note_tags() {
+        let context = context(NaiveDate::from_ymd_opt(2026, 4, 19).unwrap())
+            .with_tags(vec![String::from("work"), String::from("#q2")]);
+        let rendered = Placeholder::parse(
+            "tags: {{tags add=\"meeting, work\"}}\n{{tags style=\"hashtags\"}}".to_string(),
+            &context,
+        )
+        .unwrap();
+
+        assert_eq!(rendered, "tags: [meeting, work, q2]\n#work #q2");
+    }
 }
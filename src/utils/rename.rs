@@ -0,0 +1,327 @@
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use crate::utils::{
+    casing::normalized_name,
+    index::NoteIndex,
+    links::{rewrite_links, Link, LinkKind},
+    temp_path,
+};
+
+/// A note moving from one path to another.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NoteRename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// The new contents of a note, written to `new_path` instead of `path` when the note moves.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub new_path: Option<String>,
+    pub before: String,
+    pub after: String,
+}
+
+impl FileChange {
+    fn target(&self) -> &str {
+        self.new_path.as_deref().unwrap_or(&self.path)
+    }
+}
+
+/// The changes that move every note of `renames` and point the links of the indexed notes to
+/// their new names.
+pub fn plan_renames(index: &NoteIndex, renames: &[NoteRename]) -> io::Result<Vec<FileChange>> {
+    let names: Vec<String> = renames
+        .iter()
+        .map(|rename| normalized_name(&file_stem(&rename.from)))
+        .collect();
+
+    let mut changes = Vec::new();
+    for note in index.notes.values() {
+        let path = PathBuf::from(&note.path);
+        let rename = renames.iter().find(|rename| rename.from == path);
+        let links_to_renamed = note
+            .links
+            .iter()
+            .any(|link| names.contains(&normalized_name(&link.name)));
+        if rename.is_none() && !links_to_renamed {
+            continue;
+        }
+
+        let before = fs::read_to_string(&path)?;
+        let source_dir = path.parent().unwrap_or(Path::new(""));
+        let new_source_dir = rename
+            .and_then(|rename| rename.to.parent())
+            .unwrap_or(source_dir);
+        let after = rewrite_links(&before, |link| {
+            new_target(link, source_dir, new_source_dir, renames)
+        });
+
+        if rename.is_none() && after == before {
+            continue;
+        }
+        changes.push(FileChange {
+            path: note.path.clone(),
+            new_path: rename.map(|rename| rename.to.to_string_lossy().into_owned()),
+            before,
+            after,
+        });
+    }
+
+    for rename in renames {
+        if !changes
+            .iter()
+            .any(|change| Path::new(&change.path) == rename.from)
+        {
+            return Err(io::Error::other(format!(
+                "{} is not in the note index",
+                rename.from.display()
+            )));
+        }
+    }
+
+    Ok(changes)
+}
+
+/// The target a link should have once the notes are renamed, `None` when it stays as written.
+fn new_target(
+    link: &Link,
+    source_dir: &Path,
+    new_source_dir: &Path,
+    renames: &[NoteRename],
+) -> Option<String> {
+    let by_name = || {
+        let name = normalized_name(&link.name);
+        renames
+            .iter()
+            .find(|rename| normalized_name(&file_stem(&rename.from)) == name)
+    };
+
+    if link.kind == LinkKind::Wiki {
+        return by_name().map(|rename| file_stem(&rename.to));
+    }
+
+    let resolved = normalize_path(&source_dir.join(link.target.replace("%20", " ")));
+    let target = match renames.iter().find(|rename| rename.from == resolved) {
+        Some(rename) => relative_path(new_source_dir, &rename.to),
+        None => match by_name() {
+            // A link by file name alone, without the folders in between, stays that way.
+            Some(rename) => match link.target.rsplit_once('/') {
+                Some((folder, _)) => PathBuf::from(folder).join(rename.to.file_name()?),
+                None => PathBuf::from(rename.to.file_name()?),
+            },
+            None if new_source_dir != source_dir && resolved.exists() => {
+                relative_path(new_source_dir, &resolved)
+            }
+            None => return None,
+        },
+    };
+
+    let target = target.to_string_lossy().replace(' ', "%20");
+    (target != link.target).then_some(target)
+}
+
+/// Writes every change or none of them. The new contents are staged next to their targets
+/// first, and the notes already replaced are restored when a later one fails.
+pub fn apply_changes(changes: &[FileChange]) -> io::Result<()> {
+    let mut staged = Vec::new();
+    for change in changes {
+        let target = Path::new(change.target());
+        let result = target
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| temp_path(target))
+            .and_then(|temp| fs::write(&temp, &change.after).map(|_| temp));
+
+        match result {
+            Ok(temp) => staged.push(temp),
+            Err(err) => {
+                staged.iter().for_each(|temp| {
+                    let _ = fs::remove_file(temp);
+                });
+                return Err(err);
+            }
+        }
+    }
+
+    for (index, (change, temp)) in changes.iter().zip(&staged).enumerate() {
+        let result = fs::rename(temp, change.target()).and_then(|_| match &change.new_path {
+            Some(_) => fs::remove_file(&change.path),
+            None => Ok(()),
+        });
+
+        if let Err(err) = result {
+            staged[index..].iter().for_each(|temp| {
+                let _ = fs::remove_file(temp);
+            });
+            // The failed change left its own note in place, only a copy at the new path may
+            // remain.
+            if change.new_path.is_some() {
+                let _ = fs::remove_file(change.target());
+            }
+            for change in changes[..index].iter().rev() {
+                if change.new_path.is_some() {
+                    let _ = fs::remove_file(change.target());
+                }
+                let _ = fs::write(&change.path, &change.before);
+            }
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+/// A diff of `changes` that only shows the lines that differ, since rewriting links never adds
+/// or removes lines.
+pub fn render_diff(changes: &[FileChange]) -> String {
+    let mut diff = Vec::new();
+
+    for change in changes {
+        if let Some(new_path) = &change.new_path {
+            diff.push(format!("rename {} -> {new_path}", change.path));
+        }
+        if change.before == change.after {
+            continue;
+        }
+
+        diff.push(format!("--- {}", change.path));
+        diff.push(format!("+++ {}", change.target()));
+        for (index, (before, after)) in change.before.lines().zip(change.after.lines()).enumerate()
+        {
+            if before != after {
+                diff.push(format!("@@ line {} @@", index + 1));
+                diff.push(format!("-{before}"));
+                diff.push(format!("+{after}"));
+            }
+        }
+    }
+
+    diff.join("\n")
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// `path` with `.` and `..` resolved without touching the filesystem.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn relative_path(from_dir: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from_dir.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from
+        .iter()
+        .zip(&to)
+        .take_while(|(from, to)| from == to)
+        .count();
+
+    let mut path = PathBuf::new();
+    from[common..].iter().for_each(|_| path.push(".."));
+    to[common..]
+        .iter()
+        .for_each(|component| path.push(component));
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::markdown_files;
+    use tempfile::TempDir;
+
+    #[test]
+    fn plans_the_move_and_the_links_pointing_to_it() {
+        let notes = TempDir::new().unwrap();
+        let root = notes.path();
+        fs::create_dir_all(root.join("people")).unwrap();
+        fs::write(root.join("Ada.md"), "# Ada\n[[Babbage]]\n").unwrap();
+        fs::write(
+            root.join("people").join("Babbage.md"),
+            "[[Ada|Countess]] and [bio](../Ada.md#bio)\n",
+        )
+        .unwrap();
+
+        let mut index = NoteIndex::default();
+        index.refresh(&markdown_files(root));
+        let renames = [NoteRename {
+            from: root.join("Ada.md"),
+            to: root.join("people").join("Ada Lovelace.md"),
+        }];
+
+        let changes = plan_renames(&index, &renames).unwrap();
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].after, "# Ada\n[[Babbage]]\n");
+        assert_eq!(
+            changes[1].after,
+            "[[Ada Lovelace|Countess]] and [bio](Ada%20Lovelace.md#bio)\n"
+        );
+        assert!(render_diff(&changes).contains("@@ line 1 @@\n-[[Ada|Countess]]"));
+    }
+
+    #[test]
+    fn applies_every_change_or_none() {
+        let notes = TempDir::new().unwrap();
+        let root = notes.path();
+        fs::write(root.join("old.md"), "old").unwrap();
+        fs::write(root.join("linking.md"), "[[old]]").unwrap();
+
+        let change =
+            |path: &Path, new_path: Option<PathBuf>, before: &str, after: &str| FileChange {
+                path: path.to_string_lossy().into_owned(),
+                new_path: new_path.map(|path| path.to_string_lossy().into_owned()),
+                before: before.to_owned(),
+                after: after.to_owned(),
+            };
+
+        let failing = [
+            change(&root.join("linking.md"), None, "[[old]]", "[[new]]"),
+            change(&root.join("missing.md"), Some(root.join("new.md")), "", ""),
+        ];
+        assert!(apply_changes(&failing).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("linking.md")).unwrap(),
+            "[[old]]"
+        );
+        assert!(!root.join("new.md").exists());
+        assert!(!root.join("missing.md").exists());
+
+        let changes = [
+            change(&root.join("linking.md"), None, "[[old]]", "[[new]]"),
+            change(
+                &root.join("old.md"),
+                Some(root.join("new.md")),
+                "old",
+                "old",
+            ),
+        ];
+        apply_changes(&changes).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("linking.md")).unwrap(),
+            "[[new]]"
+        );
+        assert!(!root.join("old.md").exists());
+        assert_eq!(fs::read_to_string(root.join("new.md")).unwrap(), "old");
+    }
+}
@@ -1,14 +1,19 @@
 use std::{
+    collections::HashMap,
     fs, io,
     path::{Path, PathBuf},
 };
 
 use crate::{
-    config::{Config, JournalConfig, Sub},
+    config::{Config, JournalConfig, JournalPeriod, Sub},
     utils::{
         alternate_path,
-        date::format_date,
-        open_path_in_editor,
+        carryover::CarryOver,
+        clock::Clock,
+        date::{format_date, parse_formatted_dates, Calendar},
+        frontmatter::add_front_matter,
+        markdown::insert_into_section,
+        markdown_files, open_path_in_editor,
         placeholder::{Placeholder, TemplateContext},
     },
 };
@@ -18,6 +23,15 @@ use serde::Serialize;
 
 use super::{check_journal_note_path, command_folder_path};
 
+/// What a new note gets besides its name and template.
+#[derive(Clone, Debug, Default)]
+pub struct NoteOptions {
+    /// Rendered by `{{tags}}` and written to the front matter.
+    pub tags: Vec<String>,
+    /// Other names of the note, written to the front matter.
+    pub aliases: Vec<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TemplateEntry {
     pub name: String,
@@ -30,6 +44,8 @@ pub struct JournalEntry {
     pub format: String,
     pub template: String,
     pub folder_path: String,
+    pub period: JournalPeriod,
+    pub day_starts_at: Option<String>,
 }
 
 pub fn template_folder_path() -> Result<String, Box<dyn std::error::Error>> {
@@ -62,6 +78,8 @@ pub fn journal_entries() -> Result<Vec<JournalEntry>, Box<dyn std::error::Error>
         .unwrap_or_default()
         .into_iter()
         .map(|entry| JournalEntry {
+            period: entry.period(),
+            day_starts_at: entry.day_starts_at,
             name: entry.name,
             format: entry.format,
             template: entry.template,
@@ -110,46 +128,48 @@ pub fn check_template(template: &str) -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
-pub fn template_file_contents(template: String, context: &TemplateContext) -> Option<String> {
-    let template_folder_path = template_folder_path().ok();
-
-    if let Some(path) = template_folder_path {
-        let mut template_file_path = PathBuf::from(path);
-        let template_name_with_extension = format!("{template}.md");
+pub fn template_file_contents(
+    template: String,
+    context: &TemplateContext,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut template_file_path = PathBuf::from(template_folder_path()?);
+    let template_name_with_extension = format!("{template}.md");
 
-        template_file_path.push(&template_name_with_extension);
+    template_file_path.push(&template_name_with_extension);
 
-        let template_file_contents = fs::read_to_string(template_file_path).ok()?;
-        let parsed_template_file_contents = Placeholder::parse(template_file_contents, context);
+    let template_file_contents = fs::read_to_string(template_file_path)
+        .map_err(|_| io::Error::other(format!("failed to load template '{template}'")))?;
+    let parsed_template_file_contents = Placeholder::parse(template_file_contents, context)
+        .map_err(|err| {
+            io::Error::other(format!("failed to render template '{template}': {err}"))
+        })?;
 
-        Some(parsed_template_file_contents)
-    } else {
-        None
-    }
+    Ok(parsed_template_file_contents)
 }
 
-pub fn write_template_to_file(
-    full_path: String,
+pub fn create_file_from_template(
+    full_path: &str,
     template: String,
     context: &TemplateContext,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let template_file_contents = template_file_contents(template.clone(), context)
-        .ok_or_else(|| io::Error::other(format!("failed to load template '{template}'")))?;
+    let template_file_contents =
+        add_front_matter(template_file_contents(template, context)?, context)?;
 
-    if let Some(parent) = Path::new(&full_path).parent() {
+    if let Some(parent) = Path::new(full_path).parent() {
         fs::create_dir_all(parent)?;
     }
 
-    fs::write(&full_path, template_file_contents)?;
-    open_path_in_editor(&full_path)?;
+    fs::write(full_path, template_file_contents)?;
 
     Ok(())
 }
 
-pub fn insert_template_to_file(
+/// Creates the note `name` from the template without opening it.
+pub fn create_note_from_template(
     template: String,
     name: String,
     command: Sub,
+    options: &NoteOptions,
 ) -> Result<String, Box<dyn std::error::Error>> {
     let command_path_str = command_folder_path(command)?;
     let full_path = PathBuf::from(command_path_str)
@@ -157,32 +177,134 @@ pub fn insert_template_to_file(
         .to_string_lossy()
         .into_owned();
 
-    let context = TemplateContext::new(name, chrono::Local::now().date_naive());
+    let clock = Clock::load()?;
+    let context = TemplateContext::new(name, clock.today())
+        .with_calendar(Calendar::load()?)
+        .with_now(clock.now)
+        .with_template(template.clone())
+        .with_tags(options.tags.clone())
+        .with_aliases(options.aliases.clone());
 
-    write_template_to_file(full_path.clone(), template, &context)?;
+    create_file_from_template(&full_path, template, &context)?;
     Ok(full_path)
 }
 
-pub fn insert_template_journal(
+pub fn insert_template_to_file(
+    template: String,
+    name: String,
+    command: Sub,
+    options: &NoteOptions,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let full_path = create_note_from_template(template, name, command, options)?;
+
+    open_path_in_editor(&full_path)?;
+    Ok(full_path)
+}
+
+pub fn journal_entry_path(
     journal: &JournalConfig,
     reference_date: NaiveDate,
+    calendar: &Calendar,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    let date_formatted = format_date(reference_date, &journal.format);
+    let date_formatted =
+        format_date(reference_date, &journal.format, calendar).map_err(io::Error::other)?;
     let command_path_str = command_folder_path(Sub::Journal)?;
-    let full_path = PathBuf::from(command_path_str)
+
+    Ok(PathBuf::from(command_path_str)
         .join(&journal.folder_path)
         .join(format!("{date_formatted}.md"))
         .to_string_lossy()
-        .into_owned();
+        .into_owned())
+}
+
+/// The entries of `journal` that exist on disk with the date each was created for, oldest first.
+pub fn journal_entries_on_disk(
+    journal: &JournalConfig,
+    calendar: &Calendar,
+    today: NaiveDate,
+) -> Result<Vec<(NaiveDate, String)>, Box<dyn std::error::Error>> {
+    let folder = PathBuf::from(command_folder_path(Sub::Journal)?).join(&journal.folder_path);
+    let files: HashMap<String, String> = markdown_files(&folder)
+        .into_iter()
+        .filter_map(|path| {
+            let name = path.strip_prefix(&folder).ok()?.with_extension("");
+            Some((
+                name.to_string_lossy().into_owned(),
+                path.to_string_lossy().into_owned(),
+            ))
+        })
+        .collect();
+
+    let names: Vec<&str> = files.keys().map(String::as_str).collect();
+    let calendar = calendar.for_format(&journal.format);
+    let dates = parse_formatted_dates(&names, &journal.format, journal.period(), &calendar, today)
+        .map_err(io::Error::other)?;
+
+    let mut entries: Vec<(NaiveDate, String)> = dates
+        .into_iter()
+        .filter_map(|(name, date)| files.get(name).map(|path| (date, path.clone())))
+        .collect();
+
+    entries.sort();
+    Ok(entries)
+}
+
+/// Creates the journal entry for `reference_date` unless it already exists, without opening it.
+pub fn create_journal_entry(
+    journal: &JournalConfig,
+    reference_date: NaiveDate,
+    calendar: &Calendar,
+    clock: &Clock,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let calendar = &calendar.for_format(&journal.format);
+    let full_path = journal_entry_path(journal, reference_date, calendar)?;
 
     if let Some(existing_path) = check_journal_note_path(&full_path) {
-        open_path_in_editor(&existing_path)?;
         return Ok(existing_path);
     }
 
-    let context = TemplateContext::new(date_formatted, reference_date);
+    let title = format_date(reference_date, &journal.format, calendar).map_err(io::Error::other)?;
+    let context = TemplateContext::new(title, reference_date)
+        .with_period(journal.period())
+        .with_calendar(calendar.clone())
+        .with_now(clock.now)
+        .with_journal(journal.clone())
+        .with_template(journal.template.clone())
+        .with_tags(journal.tags.clone().unwrap_or_default())
+        .with_carryover(CarryOver::load(journal, reference_date, calendar)?);
+
+    let mut contents = add_front_matter(
+        template_file_contents(journal.template.clone(), &context)?,
+        &context,
+    )?;
+    let carryover = context.carryover.unwrap_or_default();
+    for section in journal.carry_over_sections.iter().flatten() {
+        let tasks = carryover.take(section, journal.carry_over_migrate.unwrap_or(false));
+
+        if !tasks.is_empty() {
+            contents = insert_into_section(&contents, section, &tasks.join("\n"));
+        }
+    }
 
-    write_template_to_file(full_path.clone(), journal.template.clone(), &context)?;
+    if let Some(parent) = Path::new(&full_path).parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&full_path, contents)?;
+    // The previous entry only loses its tasks once they are safe in the new one.
+    carryover.finish()?;
+
+    Ok(full_path)
+}
+
+pub fn insert_template_journal(
+    journal: &JournalConfig,
+    reference_date: NaiveDate,
+    calendar: &Calendar,
+    clock: &Clock,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let full_path = create_journal_entry(journal, reference_date, calendar, clock)?;
+
+    open_path_in_editor(&full_path)?;
     Ok(full_path)
 }
 
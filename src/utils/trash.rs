@@ -0,0 +1,224 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDateTime;
+use directories::BaseDirs;
+use serde::Serialize;
+
+use crate::{config::Config, utils::alternate_path};
+
+const DELETION_DATE_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// A note in the trash, with the path it was deleted from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TrashedNote {
+    pub path: String,
+    pub deleted_at: NaiveDateTime,
+    /// Where the note is kept in the trash.
+    pub file: PathBuf,
+}
+
+/// The configured `trash_folder_path`, or the XDG trash of the user (`$XDG_DATA_HOME/Trash`).
+/// Both use the `files` and `info` layout of the XDG trash specification.
+pub fn trash_folder() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if let Some(path) = Config::read()?.general.trash_folder_path {
+        return Ok(PathBuf::from(alternate_path(path)));
+    }
+
+    let dirs = BaseDirs::new().ok_or("could not determine the data directory")?;
+    Ok(dirs.data_dir().join("Trash"))
+}
+
+/// Moves the note at `path` into the trash, next to a `.trashinfo` file recording where it came
+/// from, and returns where it is kept.
+pub fn move_to_trash(trash: &Path, path: &Path, deleted_at: NaiveDateTime) -> io::Result<PathBuf> {
+    let files = trash.join("files");
+    let info = trash.join("info");
+    fs::create_dir_all(&files)?;
+    fs::create_dir_all(&info)?;
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut name = format!("{stem}{extension}");
+    let mut number = 1;
+    while files.join(&name).exists() || info.join(format!("{name}.trashinfo")).exists() {
+        number += 1;
+        name = format!("{stem}.{number}{extension}");
+    }
+
+    let info_path = info.join(format!("{name}.trashinfo"));
+    fs::write(
+        &info_path,
+        format!(
+            "[Trash Info]\nPath={}\nDeletionDate={}\n",
+            percent_encode(&path.to_string_lossy()),
+            deleted_at.format(DELETION_DATE_FORMAT)
+        ),
+    )?;
+
+    let file = files.join(&name);
+    move_file(path, &file).map_err(|err| {
+        let _ = fs::remove_file(&info_path);
+        err
+    })?;
+
+    Ok(file)
+}
+
+/// The notes in the trash, most recently deleted first. Entries without a readable
+/// `.trashinfo` are skipped.
+pub fn trashed_notes(trash: &Path) -> Vec<TrashedNote> {
+    let Ok(entries) = fs::read_dir(trash.join("info")) else {
+        return Vec::new();
+    };
+
+    let mut notes: Vec<TrashedNote> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let name = file_name.strip_suffix(".trashinfo")?;
+            let contents = fs::read_to_string(entry.path()).ok()?;
+
+            let path = contents
+                .lines()
+                .find_map(|line| line.strip_prefix("Path="))?;
+            let deleted_at = contents
+                .lines()
+                .find_map(|line| line.strip_prefix("DeletionDate="))
+                .and_then(|date| NaiveDateTime::parse_from_str(date, DELETION_DATE_FORMAT).ok())?;
+
+            Some(TrashedNote {
+                path: percent_decode(path),
+                deleted_at,
+                file: trash.join("files").join(name),
+            })
+        })
+        .filter(|note| note.file.is_file() && note.path.ends_with(".md"))
+        .collect();
+
+    notes.sort_by_key(|note| std::cmp::Reverse(note.deleted_at));
+    notes
+}
+
+/// Moves a note out of the trash back to where it was deleted from.
+pub fn restore_from_trash(note: &TrashedNote) -> io::Result<()> {
+    let path = Path::new(&note.path);
+    if path.exists() {
+        return Err(io::Error::other(format!(
+            "{} already exists, move it away to restore the deleted note",
+            note.path
+        )));
+    }
+
+    move_file(&note.file, path)?;
+
+    let info = note
+        .file
+        .parent()
+        .and_then(Path::parent)
+        .map(|trash| trash.join("info"))
+        .unwrap_or_default();
+    let name = note.file.file_name().unwrap_or_default().to_string_lossy();
+    let _ = fs::remove_file(info.join(format!("{name}.trashinfo")));
+
+    Ok(())
+}
+
+/// Renames `from` to `to`, copying it when they are on different file systems.
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from).map_err(|err| {
+            let _ = fs::remove_file(to);
+            err
+        })?;
+    }
+
+    Ok(())
+}
+
+fn percent_encode(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use tempfile::TempDir;
+
+    #[test]
+    fn trashes_and_restores_notes_with_the_same_name() {
+        let temp_dir = TempDir::new().unwrap();
+        let trash = temp_dir.path().join("Trash");
+        let note = temp_dir.path().join("notes").join("Café notes.md");
+        fs::create_dir_all(note.parent().unwrap()).unwrap();
+        let date = NaiveDate::from_ymd_opt(2026, 4, 19).unwrap();
+
+        fs::write(&note, "first").unwrap();
+        move_to_trash(&trash, &note, date.and_hms_opt(8, 0, 0).unwrap()).unwrap();
+        fs::write(&note, "second").unwrap();
+        let file = move_to_trash(&trash, &note, date.and_hms_opt(9, 0, 0).unwrap()).unwrap();
+
+        assert_eq!(file, trash.join("files").join("Café notes.2.md"));
+        let info =
+            fs::read_to_string(trash.join("info").join("Café notes.2.md.trashinfo")).unwrap();
+        assert!(info.contains("/notes/Caf%C3%A9%20notes.md\n"));
+
+        let trashed = trashed_notes(&trash);
+        assert_eq!(trashed.len(), 2);
+        assert_eq!(trashed[0].path, note.to_string_lossy());
+        assert_eq!(trashed[0].file, file);
+
+        restore_from_trash(&trashed[0]).unwrap();
+        assert_eq!(fs::read_to_string(&note).unwrap(), "second");
+        assert!(restore_from_trash(&trashed[1]).is_err());
+        assert_eq!(trashed_notes(&trash).len(), 1);
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::{
+    config::{Config, Sub},
+    utils::{
+        alternate_path, command_folder_path,
+        date::{parse_formatted_dates, Calendar},
+        index::{IndexedNote, NoteIndex},
+    },
+};
+
+/// A note or journal entry in the vault.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VaultNote {
+    pub name: String,
+    pub path: String,
+    /// The journal the entry belongs to, `None` for regular notes.
+    pub journal: Option<String>,
+    /// The date a journal entry was created for.
+    pub date: Option<NaiveDate>,
+}
+
+/// The journal and date written to the front matter of an entry that doesn't follow the
+/// filename format of its journal.
+fn front_matter_entry(front_matter: &BTreeMap<String, String>) -> Option<(String, NaiveDate)> {
+    let journal = front_matter.get("journal")?;
+    let date = NaiveDate::parse_from_str(front_matter.get("reference_date")?, "%Y-%m-%d").ok()?;
+
+    Some((journal.clone(), date))
+}
+
+/// The note folder and the journal folder, which may be nested in it.
+pub fn vault_folders() -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    Ok(vec![
+        PathBuf::from(command_folder_path(Sub::Note)?),
+        PathBuf::from(command_folder_path(Sub::Journal)?),
+    ])
+}
+
+/// The archive used when `archive_folder_path` isn't configured, relative to the note folder.
+pub const DEFAULT_ARCHIVE: &str = "archive";
+
+/// The configured `archive_folder_path`, relative to the note folder unless it is an absolute or
+/// `~/` path.
+pub fn archive_folder() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let folder = Config::read()?
+        .general
+        .archive_folder_path
+        .unwrap_or_else(|| DEFAULT_ARCHIVE.to_owned());
+    let folder = PathBuf::from(alternate_path(folder));
+
+    if folder.is_relative() {
+        return Ok(PathBuf::from(command_folder_path(Sub::Note)?).join(folder));
+    }
+    Ok(folder)
+}
+
+/// The indexed notes of the note folder, leaving out journal entries and archived notes even
+/// when the journal or archive folder is nested in it.
+pub fn regular_notes(index: &NoteIndex) -> Result<Vec<&IndexedNote>, Box<dyn std::error::Error>> {
+    let note_folder = command_folder_path(Sub::Note)?;
+    let journal_folder = command_folder_path(Sub::Journal)?;
+    let archive = archive_folder()?;
+
+    Ok(index
+        .notes
+        .values()
+        .filter(|note| {
+            let path = Path::new(&note.path);
+            path.starts_with(&note_folder)
+                && !path.starts_with(&journal_folder)
+                && !path.starts_with(&archive)
+        })
+        .collect())
+}
+
+/// Every indexed note, with journal entries matched to their journal and date.
+pub fn vault_notes(
+    index: &NoteIndex,
+    calendar: &Calendar,
+    today: NaiveDate,
+) -> Result<Vec<VaultNote>, Box<dyn std::error::Error>> {
+    let journal_root = PathBuf::from(command_folder_path(Sub::Journal)?);
+
+    let mut entries: HashMap<&str, (String, NaiveDate)> = HashMap::new();
+    for journal in Config::read()?.journal.unwrap_or_default() {
+        let folder = journal_root.join(&journal.folder_path);
+        let files: HashMap<String, &str> = index
+            .notes
+            .keys()
+            .filter_map(|path| {
+                let name = PathBuf::from(path)
+                    .strip_prefix(&folder)
+                    .ok()?
+                    .with_extension("");
+                Some((name.to_string_lossy().into_owned(), path.as_str()))
+            })
+            .collect();
+
+        let names: Vec<&str> = files.keys().map(String::as_str).collect();
+        let dates = parse_formatted_dates(
+            &names,
+            &journal.format,
+            journal.period(),
+            &calendar.for_format(&journal.format),
+            today,
+        )?;
+
+        for (name, date) in dates {
+            if let Some(path) = files.get(name) {
+                entries.insert(path, (journal.name.clone(), date));
+            }
+        }
+    }
+
+    Ok(index
+        .notes
+        .values()
+        .map(|note| {
+            let (journal, date) = entries
+                .get(note.path.as_str())
+                .cloned()
+                .or_else(|| front_matter_entry(&note.front_matter))
+                .unzip();
+
+            VaultNote {
+                name: note.name.clone(),
+                path: note.path.clone(),
+                journal,
+                date,
+            }
+        })
+        .collect())
+}
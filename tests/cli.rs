@@ -1,7 +1,8 @@
 use std::{
     fs,
+    io::Write,
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
 };
 
 use tempfile::TempDir;
@@ -52,13 +53,21 @@ folder_path = "weekly"
     (temp_dir, config_path)
 }
 
-fn run_zn(config_path: &Path, args: &[&str]) -> std::process::Output {
-    Command::new(env!("CARGO_BIN_EXE_zn"))
+fn zn(config_path: &Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_zn"));
+    command
+        .env(
+            "XDG_CACHE_HOME",
+            config_path.parent().unwrap().join("cache"),
+        )
+        .env("XDG_DATA_HOME", config_path.parent().unwrap().join("data"))
         .arg("--config")
-        .arg(config_path)
-        .args(args)
-        .output()
-        .unwrap()
+        .arg(config_path);
+    command
+}
+
+fn run_zn(config_path: &Path, args: &[&str]) -> std::process::Output {
+    zn(config_path).args(args).output().unwrap()
 }
 
 fn run_zn_raw(args: &[&str]) -> std::process::Output {
@@ -144,6 +153,695 @@ fn journal_no_editor_prints_existing_path() {
     assert_eq!(stdout.trim_end(), existing_path.to_string_lossy());
 }
 
+#[test]
+fn journal_bare_offset_moves_to_next_period() {
+    let (temp_dir, config_path) = test_env();
+    let output = run_zn(
+        &config_path,
+        &[
+            "--no-editor",
+            "journal",
+            "week",
+            "--date",
+            "2026-04-15",
+            "--offset",
+            "+1",
+        ],
+    );
+
+    assert!(output.status.success());
+
+    let expected_path = temp_dir
+        .path()
+        .join("journal")
+        .join("weekly")
+        .join("2026-W17.md");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim_end(), expected_path.to_string_lossy());
+}
+
+#[test]
+fn journal_range_creates_each_entry_and_prints_paths() {
+    let (temp_dir, config_path) = test_env();
+    let output = run_zn(
+        &config_path,
+        &["--no-editor", "journal", "day", "--range", "2026-W17"],
+    );
+
+    assert!(output.status.success());
+
+    let daily = temp_dir.path().join("journal").join("daily");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], daily.join("2026-04-20.md").to_string_lossy());
+    assert_eq!(lines[6], daily.join("2026-04-26.md").to_string_lossy());
+    assert!(daily.join("2026-04-23.md").is_file());
+}
+
+#[test]
+fn now_and_tz_overrides_drive_dates_and_times() {
+    let (temp_dir, config_path) = test_env();
+    fs::write(
+        temp_dir.path().join("templates").join("daily.md"),
+        "{{date}} {{time}} {{timestamp}}",
+    )
+    .unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &[
+            "--no-editor",
+            "--tz",
+            "Asia/Tokyo",
+            "--now",
+            "2026-04-19T23:30:00Z",
+            "journal",
+            "day",
+        ],
+    );
+
+    assert!(output.status.success());
+
+    let path = temp_dir
+        .path()
+        .join("journal")
+        .join("daily")
+        .join("2026-04-20.md");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim_end(), path.to_string_lossy());
+    assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        "2026-04-20 08:30 2026-04-20T08:30:00+09:00"
+    );
+}
+
+#[test]
+fn journal_day_start_keeps_late_nights_on_previous_day() {
+    let (temp_dir, config_path) = test_env();
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        r#"
+[[journal]]
+name = "night"
+format = "%Y-%m-%d"
+template = "daily"
+folder_path = "night"
+day_starts_at = "04:00"
+"#,
+    );
+    fs::write(&config_path, config).unwrap();
+    fs::write(
+        temp_dir.path().join("templates").join("daily.md"),
+        "{{date}}",
+    )
+    .unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &[
+            "--no-editor",
+            "--tz",
+            "UTC",
+            "--now",
+            "2026-04-20T01:30",
+            "journal",
+            "night",
+        ],
+    );
+
+    assert!(output.status.success());
+
+    let path = temp_dir
+        .path()
+        .join("journal")
+        .join("night")
+        .join("2026-04-19.md");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim_end(), path.to_string_lossy());
+    assert_eq!(fs::read_to_string(path).unwrap(), "2026-04-19");
+}
+
+#[test]
+fn journal_append_creates_entry_and_inserts_under_section() {
+    let (temp_dir, config_path) = test_env();
+    fs::write(
+        temp_dir.path().join("templates").join("daily.md"),
+        "# {{title}}\n\n## Log\n\n## Notes\n",
+    )
+    .unwrap();
+
+    let append = |now: &str, text: &str| {
+        run_zn(
+            &config_path,
+            &[
+                "--tz",
+                "UTC",
+                "--now",
+                now,
+                "journal",
+                "day",
+                "--append",
+                text,
+                "--section",
+                "## Log",
+            ],
+        )
+    };
+
+    assert!(append("2026-04-19T08:30", "first").status.success());
+    assert!(append("2026-04-19T09:15", "second").status.success());
+
+    let path = temp_dir
+        .path()
+        .join("journal")
+        .join("daily")
+        .join("2026-04-19.md");
+    assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        "# 2026-04-19\n\n## Log\n\n- 08:30 first\n- 09:15 second\n\n## Notes\n"
+    );
+}
+
+#[test]
+fn note_append_reads_stdin_and_appends_at_end() {
+    let (temp_dir, config_path) = test_env();
+    let mut child = zn(&config_path)
+        .args(["--now", "2026-04-19T10:00", "note", "meeting", "Inbox"])
+        .args(["--append", "-"])
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"call the bank\n")
+        .unwrap();
+
+    assert!(child.wait().unwrap().success());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("notes").join("Inbox.md")).unwrap(),
+        "# Inbox\n- 10:00 call the bank\n"
+    );
+}
+
+#[test]
+fn capture_appends_to_default_inbox_without_editor() {
+    let (temp_dir, config_path) = test_env();
+    let output = run_zn(
+        &config_path,
+        &["--now", "2026-04-19T10:00", "capture", "buy", "milk"],
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("notes").join("Inbox.md")).unwrap(),
+        "- 10:00 buy milk\n"
+    );
+}
+
+#[test]
+fn capture_uses_configured_journal_and_template() {
+    let (temp_dir, config_path) = test_env();
+    let mut config = fs::read_to_string(&config_path).unwrap();
+    config.push_str(
+        r###"
+[capture]
+journal = "day"
+template = "* [{{title}} {{time format=\"%H:%M:%S\"}}] {{text}}"
+section = "## Inbox"
+"###,
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &[
+            "--no-editor",
+            "--now",
+            "2026-04-19T10:00:05",
+            "capture",
+            "an idea",
+        ],
+    );
+
+    assert!(output.status.success());
+
+    let path = temp_dir
+        .path()
+        .join("journal")
+        .join("daily")
+        .join("2026-04-19.md");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim_end(), path.to_string_lossy());
+    assert_eq!(
+        fs::read_to_string(path).unwrap(),
+        "# 2026-04-19\n## Inbox\n\n* [2026-04-19 10:00:05] an idea\n"
+    );
+}
+
+#[test]
+fn insert_renders_template_into_note_by_name_with_backup() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(
+        temp_dir.path().join("templates").join("meeting.md"),
+        "### Meeting {{date}}\n- ",
+    )
+    .unwrap();
+    fs::write(notes.join("Project X.md"), "# Project X\n\n## Meetings\n").unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &[
+            "--no-editor",
+            "--now",
+            "2026-04-19T10:00",
+            "insert",
+            "meeting",
+            "--into",
+            "Project X",
+            "--after",
+            "## Meetings",
+        ],
+    );
+
+    assert!(output.status.success());
+
+    let path = notes.join("Project X.md");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(stdout.trim_end(), path.to_string_lossy());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "# Project X\n\n## Meetings\n\n### Meeting 2026-04-19\n- \n"
+    );
+    assert_eq!(
+        fs::read_to_string(notes.join("Project X.md.bak")).unwrap(),
+        "# Project X\n\n## Meetings\n"
+    );
+}
+
+#[test]
+fn insert_merges_template_front_matter_into_the_note() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(
+        temp_dir.path().join("templates").join("meeting.md"),
+        "---\ntitle: Meeting\naliases: [Sync]\ntags: [meeting]\n---\n### Meeting {{date}}\n",
+    )
+    .unwrap();
+    fs::write(
+        notes.join("Project X.md"),
+        "---\ntitle: Project X\n---\n# Project X\n\n## Meetings\n",
+    )
+    .unwrap();
+    fs::write(notes.join("Plain.md"), "# Plain\n").unwrap();
+
+    let insert = |into: &str| {
+        let output = run_zn(
+            &config_path,
+            &[
+                "--no-editor",
+                "--now",
+                "2026-04-19T10:00",
+                "insert",
+                "meeting",
+                "--into",
+                into,
+            ],
+        );
+        assert!(output.status.success());
+    };
+
+    insert("Project X");
+    assert_eq!(
+        fs::read_to_string(notes.join("Project X.md")).unwrap(),
+        "---\ntitle: Project X\ntags: [meeting]\n---\n# Project X\n\n## Meetings\n\n### Meeting 2026-04-19\n"
+    );
+
+    insert("Plain");
+    assert_eq!(
+        fs::read_to_string(notes.join("Plain.md")).unwrap(),
+        "---\ntags: [meeting]\n---\n# Plain\n\n### Meeting 2026-04-19\n"
+    );
+}
+
+#[test]
+fn journal_carries_over_unchecked_tasks_from_previous_entry() {
+    let (temp_dir, config_path) = test_env();
+    fs::write(
+        temp_dir.path().join("templates").join("daily.md"),
+        "# {{title}}\n\n## Tasks\n{{carryover section=\"Tasks\" migrate=\"true\"}}\n",
+    )
+    .unwrap();
+
+    let daily = temp_dir.path().join("journal").join("daily");
+    fs::create_dir_all(&daily).unwrap();
+    fs::write(daily.join("2026-04-16.md"), "## Tasks\n- [ ] stale\n").unwrap();
+    fs::write(
+        daily.join("2026-04-17.md"),
+        "## Tasks\n- [x] done\n- [ ] write report\n",
+    )
+    .unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &["--no-editor", "journal", "day", "--date", "2026-04-19"],
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(daily.join("2026-04-19.md")).unwrap(),
+        "# 2026-04-19\n\n## Tasks\n- [ ] write report\n"
+    );
+    assert_eq!(
+        fs::read_to_string(daily.join("2026-04-17.md")).unwrap(),
+        "## Tasks\n- [x] done\n- [>] write report\n"
+    );
+}
+
+#[test]
+fn journal_carries_over_tasks_once_and_migrates_after_writing() {
+    let (temp_dir, config_path) = test_env();
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        "folder_path = \"daily\"\n",
+        "folder_path = \"daily\"\ncarry_over_sections = [\"Tasks\"]\ncarry_over_migrate = true\n",
+    );
+    fs::write(&config_path, config).unwrap();
+    let template = temp_dir.path().join("templates").join("daily.md");
+    let daily = temp_dir.path().join("journal").join("daily");
+    fs::create_dir_all(&daily).unwrap();
+    fs::write(
+        daily.join("2026-04-17.md"),
+        "## Tasks\n- [ ] write report\n",
+    )
+    .unwrap();
+
+    fs::write(
+        &template,
+        "## Tasks\n{{carryover section=\"Tasks\"}}\n{{date format=\"%J\"}}\n",
+    )
+    .unwrap();
+    let failed = run_zn(
+        &config_path,
+        &["--no-editor", "journal", "day", "--date", "2026-04-18"],
+    );
+    assert!(!failed.status.success());
+    assert!(!daily.join("2026-04-18.md").exists());
+    assert_eq!(
+        fs::read_to_string(daily.join("2026-04-17.md")).unwrap(),
+        "## Tasks\n- [ ] write report\n"
+    );
+
+    fs::write(&template, "## Tasks\n{{carryover section=\"Tasks\"}}\n").unwrap();
+    let output = run_zn(
+        &config_path,
+        &["--no-editor", "journal", "day", "--date", "2026-04-18"],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(daily.join("2026-04-18.md")).unwrap(),
+        "## Tasks\n- [ ] write report\n"
+    );
+    assert_eq!(
+        fs::read_to_string(daily.join("2026-04-17.md")).unwrap(),
+        "## Tasks\n- [>] write report\n"
+    );
+}
+
+#[test]
+fn weekly_template_embeds_daily_sections_and_notes() {
+    let (temp_dir, config_path) = test_env();
+    fs::write(
+        temp_dir.path().join("templates").join("weekly.md"),
+        "{{embed journal=\"day\" range=\"week\" section=\"Highlights\"}}\n---\n{{embed note=\"Project X\" section=\"## Status\"}}",
+    )
+    .unwrap();
+
+    let daily = temp_dir.path().join("journal").join("daily");
+    fs::create_dir_all(&daily).unwrap();
+    let entries = [
+        ("2026-04-12", "## Highlights\n- previous week"),
+        ("2026-04-13", "## Highlights\n- monday\n## Notes\n- skip"),
+        ("2026-04-14", "## Notes\n- no highlights"),
+        ("2026-04-16", "## Highlights\n\n- thursday\n"),
+    ];
+    for (name, contents) in entries {
+        fs::write(daily.join(format!("{name}.md")), contents).unwrap();
+    }
+    fs::write(
+        temp_dir.path().join("notes").join("Project X.md"),
+        "# Project X\n## Status\nOn track\n",
+    )
+    .unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &["--no-editor", "journal", "week", "--date", "2026-W16"],
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(
+            temp_dir
+                .path()
+                .join("journal")
+                .join("weekly")
+                .join("2026-W16.md")
+        )
+        .unwrap(),
+        "[[2026-04-13]]\n- monday\n\n[[2026-04-16]]\n- thursday\n---\nOn track"
+    );
+}
+
+#[test]
+fn note_template_lists_backlinks_to_new_note() {
+    let (temp_dir, config_path) = test_env();
+    fs::write(
+        temp_dir.path().join("templates").join("meeting.md"),
+        "# {{title}}\n\n{{backlinks}}",
+    )
+    .unwrap();
+
+    let notes = temp_dir.path().join("notes");
+    fs::write(notes.join("Computing.md"), "by [[Ada Lovelace|Ada]]").unwrap();
+    fs::write(notes.join("Engines.md"), "[[Ada Lovelace]] and [[Babbage]]").unwrap();
+    fs::write(notes.join("Unrelated.md"), "[[Someone Else]]").unwrap();
+    let daily = temp_dir.path().join("journal").join("daily");
+    fs::create_dir_all(&daily).unwrap();
+    fs::write(
+        daily.join("2026-04-19.md"),
+        "[read](../../notes/Ada%20Lovelace.md)",
+    )
+    .unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &["--no-editor", "note", "meeting", "Ada Lovelace"],
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(notes.join("Ada Lovelace.md")).unwrap(),
+        "# Ada Lovelace\n\n- [[2026-04-19]]\n- [[Computing]]\n- [[Engines]]"
+    );
+}
+
+#[test]
+fn links_show_outgoing_and_incoming_links() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(notes.join("Ada.md"), "[[Engines]] [[Missing]]").unwrap();
+    fs::write(notes.join("Engines.md"), "\n[[Ada]]").unwrap();
+
+    let output = run_zn(&config_path, &["links", "Ada"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "outgoing:\n  Engines -> {}\n  Missing -> (missing)\nincoming:\n  {}:2\n",
+            notes.join("Engines.md").display(),
+            notes.join("Engines.md").display(),
+        )
+    );
+}
+
+#[test]
+fn links_dangling_create_skips_journal_names() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(notes.join("Ada.md"), "[[Engines]] [[2026-04-18]]").unwrap();
+
+    let dangling = run_zn(&config_path, &["links", "--dangling"]);
+    assert!(dangling.status.success());
+    assert_eq!(
+        String::from_utf8(dangling.stdout).unwrap().lines().count(),
+        2
+    );
+
+    let output = run_zn(
+        &config_path,
+        &["links", "--dangling", "--create", "--template", "meeting"],
+    );
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        notes.join("Engines.md").to_string_lossy()
+    );
+    assert_eq!(
+        fs::read_to_string(notes.join("Engines.md")).unwrap(),
+        "# Engines"
+    );
+    assert!(!notes.join("2026-04-18.md").exists());
+}
+
+#[test]
+fn search_filters_by_journal_and_range_with_context() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    let daily = temp_dir.path().join("journal").join("daily");
+    fs::create_dir_all(&daily).unwrap();
+    fs::write(notes.join("Plans.md"), "Budget review").unwrap();
+    fs::write(daily.join("2026-04-17.md"), "intro\nBudget draft\noutro").unwrap();
+    fs::write(daily.join("2026-04-20.md"), "budget final").unwrap();
+
+    let all = run_zn(&config_path, &["search", "budget"]);
+    assert!(all.status.success());
+    assert_eq!(String::from_utf8(all.stdout).unwrap().lines().count(), 3);
+
+    let case_sensitive = run_zn(&config_path, &["search", "Budget", "--journal", "day"]);
+    assert_eq!(
+        String::from_utf8(case_sensitive.stdout).unwrap(),
+        format!("{}:2:Budget draft\n", daily.join("2026-04-17.md").display())
+    );
+
+    let output = run_zn(
+        &config_path,
+        &[
+            "search",
+            "b.dget",
+            "--regex",
+            "--range",
+            "2026-04-01..2026-04-18",
+            "--journal",
+            "day",
+            "-C",
+            "1",
+        ],
+    );
+
+    assert!(output.status.success());
+    let path = daily.join("2026-04-17.md");
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "{0}-1-intro\n{0}:2:Budget draft\n{0}-3-outro\n",
+            path.display()
+        )
+    );
+
+    // 2026-W16 runs from 2026-04-13 to 2026-04-19, so it overlaps a range starting mid-week.
+    let weekly = temp_dir.path().join("journal").join("weekly");
+    fs::create_dir_all(&weekly).unwrap();
+    fs::write(weekly.join("2026-W16.md"), "budget plan").unwrap();
+    fs::write(weekly.join("2026-W15.md"), "budget plan").unwrap();
+    let output = run_zn(
+        &config_path,
+        &[
+            "search",
+            "plan",
+            "--range",
+            "2026-04-17..2026-04-30",
+            "--journal",
+            "week",
+        ],
+    );
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!("{}:1:budget plan\n", weekly.join("2026-W16.md").display())
+    );
+
+    let unknown = run_zn(&config_path, &["search", "budget", "--journal", "dya"]);
+    assert!(!unknown.status.success());
+    assert!(String::from_utf8(unknown.stderr)
+        .unwrap()
+        .contains("no journal entry found for 'dya'"));
+}
+
+#[test]
+fn index_status_reports_pending_changes_until_rebuilt() {
+    let (temp_dir, config_path) = test_env();
+    fs::write(temp_dir.path().join("notes").join("Ada.md"), "# Ada").unwrap();
+
+    let before = run_zn(&config_path, &["index", "status"]);
+    let stdout = String::from_utf8(before.stdout).unwrap();
+    assert!(stdout.contains("notes: 0\n"));
+    assert!(stdout.contains("updated: never\n"));
+    assert!(stdout.contains("pending: 1 added, 0 updated, 0 removed"));
+
+    let rebuild = run_zn(&config_path, &["index", "rebuild"]);
+    assert!(rebuild.status.success());
+    let cache = temp_dir.path().join("cache").join("zapnote");
+    assert!(String::from_utf8(rebuild.stdout)
+        .unwrap()
+        .starts_with(&format!("indexed 1 notes in {}", cache.display())));
+
+    fs::write(
+        temp_dir.path().join("notes").join("Ada.md"),
+        "# Ada Lovelace",
+    )
+    .unwrap();
+    let after = run_zn(&config_path, &["index", "status"]);
+    let stdout = String::from_utf8(after.stdout).unwrap();
+    assert!(stdout.contains("notes: 1\n"));
+    assert!(stdout.contains("pending: 0 added, 1 updated, 0 removed"));
+}
+
+#[test]
+fn open_ranks_fuzzy_matches_and_picks_from_stdin() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(notes.join("project-kickoff.md"), "# Project Kickoff").unwrap();
+    fs::write(notes.join("pancakes.md"), "# Pancakes").unwrap();
+    fs::write(notes.join("unrelated.md"), "# Unrelated").unwrap();
+
+    let top = run_zn(&config_path, &["--no-editor", "open", "proj", "kick"]);
+    assert!(top.status.success());
+    assert_eq!(
+        String::from_utf8(top.stdout).unwrap().trim_end(),
+        notes.join("project-kickoff.md").to_string_lossy()
+    );
+
+    let list = run_zn(&config_path, &["open", "pk", "--list"]);
+    assert_eq!(
+        String::from_utf8(list.stdout).unwrap(),
+        format!(
+            " 1. Project Kickoff  {}\n 2. Pancakes  {}\n",
+            notes.join("project-kickoff.md").display(),
+            notes.join("pancakes.md").display()
+        )
+    );
+
+    let mut child = zn(&config_path)
+        .args(["--no-editor", "open", "pk", "--pick"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"2\n").unwrap();
+    let picked = child.wait_with_output().unwrap();
+
+    assert!(picked.status.success());
+    assert!(String::from_utf8(picked.stdout)
+        .unwrap()
+        .ends_with(&format!("{}\n", notes.join("pancakes.md").display())));
+}
+
 #[test]
 fn help_flag_prints_help_without_debug_error_wrapper() {
     let output = run_zn_raw(&["--help"]);
@@ -211,3 +909,560 @@ fn build_script_generates_completion_files_for_zn() {
     assert!(fish.contains("complete -c zn"));
     assert!(zsh.contains("#compdef zn"));
 }
+
+#[test]
+fn note_opens_near_duplicate_and_doctor_reports_it() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(notes.join("my-note.md"), "# My Note").unwrap();
+
+    let output = run_zn(&config_path, &["--no-editor", "note", "meeting", "My Note"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("matches the existing note"));
+    assert!(!notes.join("My Note.md").exists());
+
+    fs::write(notes.join("Mý Note.md"), "# Mý Note").unwrap();
+    let output = run_zn(&config_path, &["doctor", "duplicates"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("mynote:\n"));
+    assert!(stdout.contains(&notes.join("my-note.md").to_string_lossy().into_owned()));
+    assert!(stdout.contains(&notes.join("Mý Note.md").to_string_lossy().into_owned()));
+}
+
+#[test]
+fn tags_counts_front_matter_and_inline_tags() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(
+        temp_dir.path().join("templates").join("meeting.md"),
+        "---\ntags: {{tags add=\"meeting\"}}\n---\n# {{title}}\n",
+    )
+    .unwrap();
+    fs::write(notes.join("idea.md"), "An #idea for #work/q2").unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &["--no-editor", "note", "meeting", "Kickoff", "--tag", "work"],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(notes.join("Kickoff.md")).unwrap(),
+        "---\ntags: [meeting, work]\n---\n# Kickoff\n"
+    );
+
+    let output = run_zn(&config_path, &["tags"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("   1  meeting\n"));
+    assert!(stdout.contains("   1  work/q2\n"));
+
+    let output = run_zn(&config_path, &["tags", "#work"]);
+    let mut paths: Vec<&str> = std::str::from_utf8(&output.stdout)
+        .unwrap()
+        .lines()
+        .collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            notes.join("Kickoff.md").to_string_lossy(),
+            notes.join("idea.md").to_string_lossy()
+        ]
+    );
+}
+
+#[test]
+fn front_matter_is_merged_into_created_notes() {
+    let (temp_dir, config_path) = test_env();
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        "note_case_style = \"original\"",
+        "note_case_style = \"kebab\"\nfront_matter = \"yaml\"",
+    );
+    fs::write(&config_path, config).unwrap();
+    fs::write(
+        temp_dir.path().join("templates").join("meeting.md"),
+        "---\ntags: [meeting]\n---\n# {{title}}\n",
+    )
+    .unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &[
+            "--no-editor",
+            "--tz",
+            "UTC",
+            "--now",
+            "2026-04-19T08:30",
+            "note",
+            "meeting",
+            "Project Kickoff",
+            "--tag",
+            "work",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("notes").join("project-kickoff.md")).unwrap(),
+        "---\ntitle: project-kickoff\ncreated: 2026-04-19T08:30:00Z\ntemplate: meeting\n\
+         aliases: [Project Kickoff]\ntags: [meeting]\n---\n# project-kickoff\n"
+    );
+
+    let output = run_zn(
+        &config_path,
+        &[
+            "--no-editor",
+            "--tz",
+            "UTC",
+            "--now",
+            "2026-04-19T08:30",
+            "journal",
+            "day",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(
+            temp_dir
+                .path()
+                .join("journal")
+                .join("daily")
+                .join("2026-04-19.md")
+        )
+        .unwrap(),
+        "---\ntitle: \"2026-04-19\"\ncreated: 2026-04-19T08:30:00Z\ntemplate: daily\n\
+         journal: day\nreference_date: 2026-04-19\n---\n# 2026-04-19"
+    );
+}
+
+#[test]
+fn mv_renames_note_and_rewrites_links() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    let daily = temp_dir.path().join("journal").join("daily");
+    fs::create_dir_all(&daily).unwrap();
+    fs::write(notes.join("Old Name.md"), "# Old Name\n").unwrap();
+    fs::write(notes.join("other.md"), "See [[Old Name|it]]\n").unwrap();
+    fs::write(
+        daily.join("2026-04-19.md"),
+        "- [old](../../notes/Old%20Name.md)\n",
+    )
+    .unwrap();
+
+    let output = run_zn(&config_path, &["mv", "Old Name", "New Name", "--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("-See [[Old Name|it]]\n+See [[New Name|it]]\n"));
+    assert!(stdout.contains("+- [old](../../notes/New%20Name.md)\n"));
+    assert!(notes.join("Old Name.md").exists());
+
+    let output = run_zn(&config_path, &["mv", "Old Name", "New Name"]);
+    assert!(output.status.success());
+    assert!(!notes.join("Old Name.md").exists());
+    assert_eq!(
+        fs::read_to_string(notes.join("New Name.md")).unwrap(),
+        "# Old Name\n"
+    );
+    assert_eq!(
+        fs::read_to_string(notes.join("other.md")).unwrap(),
+        "See [[New Name|it]]\n"
+    );
+    assert_eq!(
+        fs::read_to_string(daily.join("2026-04-19.md")).unwrap(),
+        "- [old](../../notes/New%20Name.md)\n"
+    );
+
+    let output = run_zn(&config_path, &["mv", "New Name", "other"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn migrate_case_renames_notes_and_refuses_collisions() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(notes.join("My Note.md"), "[[Other Note]]\n").unwrap();
+    fs::write(notes.join("Other Note.md"), "[[My Note|mine]]\n").unwrap();
+    fs::write(notes.join("kept.md"), "").unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &["migrate", "case", "--to", "kebab", "--dry-run"],
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!(
+        "rename {} -> {}",
+        notes.join("My Note.md").display(),
+        notes.join("my-note.md").display()
+    )));
+    assert!(notes.join("My Note.md").exists());
+
+    let output = run_zn(&config_path, &["migrate", "case", "--to", "kebab"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "renamed 2 notes, updated links in 0 notes\n"
+    );
+    assert_eq!(
+        fs::read_to_string(notes.join("my-note.md")).unwrap(),
+        "[[other-note]]\n"
+    );
+    assert_eq!(
+        fs::read_to_string(notes.join("other-note.md")).unwrap(),
+        "[[my-note|mine]]\n"
+    );
+
+    fs::write(notes.join("Kept.md"), "").unwrap();
+    let output = run_zn(&config_path, &["migrate", "case", "--to", "kebab"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("these would collide"));
+    assert!(notes.join("Kept.md").exists());
+}
+
+#[test]
+fn archive_rm_and_restore_move_notes_around() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::create_dir_all(notes.join("projects")).unwrap();
+    fs::write(notes.join("projects").join("Apollo.md"), "# Apollo\n").unwrap();
+    fs::write(notes.join("index.md"), "[[Apollo]]\n").unwrap();
+
+    let output = run_zn(
+        &config_path,
+        &["--now", "2026-04-19T08:30", "archive", "projects/Apollo.md"],
+    );
+    assert!(output.status.success());
+    let archived = notes.join("archive").join("projects").join("Apollo.md");
+    assert_eq!(
+        fs::read_to_string(&archived).unwrap(),
+        "---\narchived: 2026-04-19\n---\n# Apollo\n"
+    );
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("warning: 1 notes still link to"));
+
+    let output = run_zn(&config_path, &["restore", "apollo"]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(notes.join("projects").join("Apollo.md")).unwrap(),
+        "# Apollo\n"
+    );
+
+    let output = run_zn(&config_path, &["rm", "index"]);
+    assert!(output.status.success());
+    assert!(!notes.join("index.md").exists());
+    let trash = temp_dir.path().join("data").join("Trash");
+    assert_eq!(
+        fs::read_to_string(trash.join("files").join("index.md")).unwrap(),
+        "[[Apollo]]\n"
+    );
+
+    let output = run_zn(&config_path, &["restore", "--list"]);
+    assert!(String::from_utf8(output.stdout)
+        .unwrap()
+        .contains(&notes.join("index.md").to_string_lossy().into_owned()));
+
+    let output = run_zn(&config_path, &["restore", "index"]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(notes.join("index.md")).unwrap(),
+        "[[Apollo]]\n"
+    );
+    assert!(!trash.join("files").join("index.md").exists());
+}
+
+#[test]
+fn archived_notes_leave_search_and_tags() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    fs::write(notes.join("Apollo.md"), "Launch budget #rocket\n").unwrap();
+    fs::write(notes.join("Gemini.md"), "Docking budget #capsule\n").unwrap();
+
+    let output = run_zn(&config_path, &["search", "budget"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap().lines().count(), 2);
+
+    let output = run_zn(&config_path, &["archive", "Apollo"]);
+    assert!(output.status.success());
+    assert!(notes.join("archive").join("Apollo.md").exists());
+
+    let output = run_zn(&config_path, &["search", "budget"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "{}:1:Docking budget #capsule\n",
+            notes.join("Gemini.md").display()
+        )
+    );
+
+    let output = run_zn(&config_path, &["tags"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("capsule"));
+    assert!(!stdout.contains("rocket"));
+}
+
+#[test]
+fn stats_counts_entries_and_journaling_streaks() {
+    let (temp_dir, config_path) = test_env();
+    let notes = temp_dir.path().join("notes");
+    let daily = temp_dir.path().join("journal").join("daily");
+    let weekly = temp_dir.path().join("journal").join("weekly");
+    fs::create_dir_all(&daily).unwrap();
+    fs::create_dir_all(&weekly).unwrap();
+    fs::write(
+        notes.join("Kickoff.md"),
+        "---\ntemplate: meeting\ncreated: 2026-04-02\n---\nAgenda and owners\n",
+    )
+    .unwrap();
+    for day in [
+        "2026-03-30",
+        "2026-04-10",
+        "2026-04-11",
+        "2026-04-18",
+        "2026-04-19",
+    ] {
+        fs::write(daily.join(format!("{day}.md")), "Wrote a little").unwrap();
+    }
+    for week in ["2026-W14", "2026-W15", "2026-W16"] {
+        fs::write(weekly.join(format!("{week}.md")), "Plans").unwrap();
+    }
+
+    let output = run_zn(
+        &config_path,
+        &["--now", "2026-04-20T09:00:00Z", "stats", "--json"],
+    );
+
+    assert!(output.status.success());
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["notes"], 1);
+    assert_eq!(stats["journal_entries"], 8);
+    assert_eq!(stats["words"], 3 + 5 * 3 + 3);
+    assert_eq!(stats["templates"]["meeting"], 1);
+    assert_eq!(stats["templates"]["daily"], 5);
+    assert_eq!(stats["journals"][0]["entries_per_month"]["2026-03"], 1);
+    assert_eq!(stats["journals"][0]["entries_per_month"]["2026-04"], 4);
+    assert_eq!(stats["journals"][0]["current_streak"], 2);
+    assert_eq!(stats["journals"][0]["longest_streak"], 2);
+    assert_eq!(stats["journals"][1]["current_streak"], 3);
+
+    let since = run_zn(
+        &config_path,
+        &[
+            "--now",
+            "2026-04-20T09:00:00Z",
+            "stats",
+            "--since",
+            "2026-04-11",
+            "--json",
+        ],
+    );
+    let stats: serde_json::Value = serde_json::from_slice(&since.stdout).unwrap();
+    assert_eq!(stats["notes"], 0);
+    assert_eq!(stats["journals"][0]["entries"], 3);
+}
+
+#[test]
+fn calendar_formats_ignore_fiscal_year_start() {
+    let (temp_dir, config_path) = test_env();
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        "note_case_style = \"original\"\n",
+        "note_case_style = \"original\"\nfiscal_year_start_month = 2\n",
+    ) + r#"
+[[journal]]
+name = "q"
+format = "%Y-Q%Q"
+template = "daily"
+folder_path = "quarters"
+
+[[journal]]
+name = "fq"
+format = "FY%FY-Q%FQ"
+template = "daily"
+folder_path = "fiscal"
+
+[[journal]]
+name = "y"
+format = "%Y"
+template = "daily"
+folder_path = "years"
+"#;
+    fs::write(&config_path, config).unwrap();
+    fs::write(
+        temp_dir.path().join("templates").join("daily.md"),
+        "{{date}}",
+    )
+    .unwrap();
+
+    let journal = temp_dir.path().join("journal");
+    let create = |name: &str, date: &str| {
+        let output = run_zn(
+            &config_path,
+            &["--no-editor", "journal", name, "--date", date],
+        );
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .trim_end()
+            .to_owned()
+    };
+
+    let quarter = journal.join("quarters").join("2026-Q1.md");
+    assert_eq!(create("q", "2026-01-15"), quarter.to_string_lossy());
+    assert_eq!(fs::read_to_string(quarter).unwrap(), "2026-01-01");
+
+    let fiscal = journal.join("fiscal").join("FY2026-Q4.md");
+    assert_eq!(create("fq", "2026-01-15"), fiscal.to_string_lossy());
+    assert_eq!(fs::read_to_string(fiscal).unwrap(), "2025-11-01");
+
+    let year = journal.join("years").join("2026.md");
+    assert_eq!(create("y", "2026-03-15"), year.to_string_lossy());
+    assert_eq!(fs::read_to_string(year).unwrap(), "2026-01-01");
+}
+
+#[test]
+fn half_year_and_fiscal_journals_name_their_periods() {
+    let (temp_dir, config_path) = test_env();
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        "note_case_style = \"original\"\n",
+        "note_case_style = \"original\"\nfiscal_year_start_month = 7\n",
+    ) + r#"
+[[journal]]
+name = "half"
+format = "%Y-H%EH"
+template = "daily"
+folder_path = "halves"
+
+[[journal]]
+name = "fiscal"
+format = "FY%FY-Q%FQ"
+template = "daily"
+folder_path = "fiscal"
+"#;
+    fs::write(&config_path, config).unwrap();
+    fs::write(
+        temp_dir.path().join("templates").join("daily.md"),
+        "{{date}}",
+    )
+    .unwrap();
+
+    let journal = temp_dir.path().join("journal");
+    let create = |args: &[&str]| {
+        let output = run_zn(&config_path, &[&["--no-editor", "journal"], args].concat());
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .trim_end()
+            .to_owned()
+    };
+
+    let half = journal.join("halves").join("2026-H2.md");
+    assert_eq!(
+        create(&["half", "--date", "2026-08-10"]),
+        half.to_string_lossy()
+    );
+    assert_eq!(fs::read_to_string(half).unwrap(), "2026-07-01");
+
+    let next = journal.join("halves").join("2027-H1.md");
+    assert_eq!(
+        create(&["half", "--date", "2026-08-10", "--offset", "+1"]),
+        next.to_string_lossy()
+    );
+
+    let fiscal = journal.join("fiscal").join("FY2027-Q1.md");
+    assert_eq!(
+        create(&["fiscal", "--date", "2026-08-10"]),
+        fiscal.to_string_lossy()
+    );
+    assert_eq!(fs::read_to_string(fiscal).unwrap(), "2026-07-01");
+    assert_eq!(
+        create(&["fiscal", "--date", "FY2027-Q3"]),
+        journal
+            .join("fiscal")
+            .join("FY2027-Q3.md")
+            .to_string_lossy()
+    );
+}
+
+#[test]
+fn journal_accepts_natural_language_dates_and_compound_offsets() {
+    let (temp_dir, config_path) = test_env();
+    let daily = temp_dir.path().join("journal").join("daily");
+    let create = |args: &[&str]| {
+        let now = ["--no-editor", "--tz", "UTC", "--now", "2026-04-20T09:00"];
+        let output = run_zn(
+            &config_path,
+            &[&now[..], &["journal", "day"], args].concat(),
+        );
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .trim_end()
+            .to_owned()
+    };
+
+    assert_eq!(
+        create(&["--date", "last friday"]),
+        daily.join("2026-04-17.md").to_string_lossy()
+    );
+    assert_eq!(
+        create(&["--offset", "+1 week -2 days"]),
+        daily.join("2026-04-25.md").to_string_lossy()
+    );
+    assert_eq!(
+        create(&["--date", "2026-04-20", "--offset", "-1 week +2 days"]),
+        daily.join("2026-04-15.md").to_string_lossy()
+    );
+    assert_eq!(
+        create(&["--date", "2026-04-20", "--offset", "-1 day"]),
+        daily.join("2026-04-19.md").to_string_lossy()
+    );
+    assert_eq!(
+        create(&["--date", "3 days ago"]),
+        daily.join("2026-04-17.md").to_string_lossy()
+    );
+
+    let output = run_zn(&config_path, &["journal", "day", "--date", "someday"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("unsupported --date 'someday'"));
+}
+
+#[test]
+fn weekday_offsets_skip_weekends_and_holidays() {
+    let (temp_dir, config_path) = test_env();
+    let holidays = temp_dir.path().join("holidays.toml");
+    fs::write(&holidays, "holidays = [\"2026-04-17\"]\n").unwrap();
+    let config = fs::read_to_string(&config_path).unwrap().replace(
+        "note_case_style = \"original\"\n",
+        &format!(
+            "note_case_style = \"original\"\nholidays_path = \"{}\"\n",
+            holidays.display()
+        ),
+    );
+    fs::write(&config_path, config).unwrap();
+
+    let daily = temp_dir.path().join("journal").join("daily");
+    let create = |offset: &[&str]| {
+        let now = ["--no-editor", "--tz", "UTC", "--now", "2026-04-20T09:00"];
+        let output = run_zn(
+            &config_path,
+            &[&now[..], &["journal", "day", "--offset"], offset].concat(),
+        );
+        assert!(output.status.success());
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .trim_end()
+            .to_owned()
+    };
+
+    assert_eq!(
+        create(&["-1", "weekday"]),
+        daily.join("2026-04-16.md").to_string_lossy()
+    );
+    assert_eq!(
+        create(&["+5 business days"]),
+        daily.join("2026-04-27.md").to_string_lossy()
+    );
+}